@@ -1,108 +1,121 @@
 from .elektron import Draw as RDraw
-from .elektron import Line, Dot, Label, Element, Simulation, Circuit
+from .elektron import (Line, Bus, BusEntry, bus_members, length, Dot, Label, GlobalLabel,
+                       HierarchicalLabel, Element, Sheet, Text, Polyline, Rectangle,
+                       PlotOptions, Theme, Simulation, Circuit)
+from .elektron import (ElektronError, SymbolNotFoundError, PinNotFoundError,
+                       LibraryError, SimulationError)
 
-print("load elektron py")
-PLOTS = []
+import os
+
+THEMES = os.path.join(os.path.dirname(__file__), "themes")
 
-def plots():
-    return PLOTS
+def theme(name):
+    """get a theme by name, from the shipped theme files or built into the plotter."""
+    path = os.path.join(THEMES, name + ".css")
+    if os.path.exists(path):
+        return Theme.load(path)
+    return Theme(name)
 
 class Draw:
-    def __init__(self, library_path):
-        self.el = RDraw(library_path)
+    def __init__(self, library_path, grid=1.27):
+        self.el = RDraw(library_path, grid)
+
+    @classmethod
+    def open(cls, path, library_path, grid=1.27):
+        draw = cls.__new__(cls)
+        draw.el = RDraw.open(path, library_path, grid)
+        return draw
+
+    @property
+    def grid(self):
+        return self.el.grid
+
+    @grid.setter
+    def grid(self, grid):
+        self.el.grid = grid
+
+    @property
+    def unit(self):
+        return self.el.unit
+
+    @property
+    def warnings(self):
+        return self.el.warnings
+
+    @property
+    def direction(self):
+        return self.el.direction
+
+    @direction.setter
+    def direction(self, direction):
+        self.el.direction = direction
+
+    def __getitem__(self, name):
+        return self.el.anchor(name)
+
+    def __contains__(self, name):
+        return name in self.el
 
     def add(self, item):
         self.el.add(item)
 
-    def write(self, filename):
-        self.el.write(filename)
+    def anchor(self, name):
+        return self.el.anchor(name)
 
-    def plot(self, filename, border, scale, imagetype, netlist=False):
-        global PLOTS
-        PLOTS = self.el.plot(filename, border, scale, imagetype, netlist)
+    def bus_alias(self, name, members):
+        self.el.bus_alias(name, members)
 
-    def circuit(self, pathlist):
-        return self.el.circuit(pathlist)
+    def bus_members(self, name):
+        return self.el.bus_members(name)
 
+    def sheet(self, sheet):
+        return self.el.sheet(sheet)
 
-import os
-import sys
+    def branch(self, direction=None):
+        return self.el.branch(direction)
 
-from io import BytesIO
-from subprocess import run
+    def goto(self, reference, pin=None):
+        self.el.goto(reference, pin)
 
-from matplotlib import interactive, is_interactive
-from matplotlib._pylab_helpers import Gcf
-from matplotlib.backend_bases import (_Backend, FigureManagerBase)
-from matplotlib.backends.backend_agg import FigureCanvasAgg
+    def annotate(self, order="xy"):
+        self.el.annotate(order)
 
-if sys.flags.interactive:
-    interactive(True)
+    def erc(self):
+        return self.el.erc()
 
-class FigureManagerElektron(FigureManagerBase):
+    def symbols(self):
+        return self.el.symbols()
 
-    @classmethod
-    def _run(cls, *cmd):
-        def f(*args, output=True, **kwargs):
-            if output:
-                kwargs['capture_output'] = True
-                kwargs['text'] = True
-            r = run(cmd + args, **kwargs)
-            if output:
-                return r.stdout.rstrip()
-        return f
-
-    def show(self):
-
-        # icat = __class__._run('kitty', '+kitten', 'icat')
-
-        # if os.environ.get('MPLBACKEND_KITTY_SIZING', 'automatic') != 'manual':
-
-        #     tput = __class__._run('tput')
-
-        #     # gather terminal dimensions
-        #     rows = int(tput('lines'))
-        #     px = icat('--print-window-size')
-        #     px = list(map(int, px.split('x')))
-
-        #     # account for post-display prompt scrolling
-        #     # 3 line shift for [\n, <matplotlib.axes…, >>>] after the figure
-        #     px[1] -= int(3*(px[1]/rows))
-
-        #     # resize figure to terminal size & aspect ratio
-        #     dpi = self.canvas.figure.dpi
-        #     self.canvas.figure.set_size_inches((px[0] / dpi, px[1] / dpi))
-        
-        with BytesIO() as buf:
-            global PLOTS
-            self.canvas.figure.savefig(buf, format='svg')
-            PLOTS = [list(bytes(buf.getvalue()))]
-            # icat('--align', 'left', output=False, input=buf.getbuffer())
-
-
-@_Backend.export
-class _BackendElektron(_Backend):
-
-    FigureCanvas = FigureCanvasAgg
-    FigureManager = FigureManagerElektron
-
-    # Noop function instead of None signals that
-    # this is an "interactive" backend
-    mainloop = lambda: None
-
-    # XXX: `draw_if_interactive` isn't really intended for
-    # on-shot rendering. We run the risk of being called
-    # on a figure that isn't completely rendered yet, so
-    # we skip draw calls for figures that we detect as
-    # not being fully initialized yet. Our heuristic for
-    # that is the presence of axes on the figure.
-    @classmethod
-    def draw_if_interactive(cls):
-        manager = Gcf.get_active()
-        if is_interactive() and manager.canvas.figure.get_axes():
-            cls.show()
+    def symbol(self, reference, unit=1):
+        return self.el.symbol(reference, unit)
+
+    def pins(self, reference):
+        return self.el.pins(reference)
+
+    def bom(self, format="csv", group_by=None, hidden=False, exclude=None):
+        return self.el.bom(format, group_by, hidden, exclude)
+
+    def connect(self, start, end):
+        self.el.connect(start, end)
+
+    def write(self, filename):
+        self.el.write(filename)
+
+    def plot(self, filename=None, options=None):
+        return self.el.plot(filename, options)
+
+    def export_pdf(self, path, options=None):
+        self.el.export_pdf(path, options)
+
+    def _repr_svg_(self):
+        return self.el._repr_svg_()
+
+    def _repr_png_(self):
+        return self.el._repr_png_()
+
+    def _repr_html_(self):
+        return self.el._repr_html_()
+
+    def circuit(self, pathlist):
+        return self.el.circuit(pathlist)
 
-    @classmethod
-    def show(cls, *args, **kwargs):
-        _Backend.show(*args, **kwargs)
-        Gcf.destroy_all()
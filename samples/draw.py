@@ -1,4 +1,4 @@
-from elektron import Line, Dot, Label, Element, Draw, Simulation
+from elektron import Line, Dot, Label, Element, Draw, PlotOptions, Simulation
 draw = Draw(["/usr/share/kicad/symbols"])
 draw.add(Label("INPUT").rotate(180))
 draw.add(Line())
@@ -27,7 +27,7 @@ draw.add(Element("GND", "power:GND", value="GND", unit=1).at("R3", "1"))
 draw.add(Element("U1", "4xxx:4069", value="U1", unit=7, Spice_Primitive="X", Spice_Model="4069UB", on_schema="no"))
 draw.add(Element("GND", "power:GND", value="GND", unit=1, on_schema="no").at("U1", "14"))
 draw.add(Element("GND", "power:+5V", value="+5V", unit=1, on_schema="no").at("U1", "7"))
-res = draw.plot("draw.svg", False, 5)
+res = draw.plot("draw.svg", PlotOptions(scale=5))
 
 draw.write("draw.kicad_sch")
 
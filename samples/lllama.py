@@ -1,4 +1,4 @@
-from elektron import Line, Dot, Label, Element, Draw, Simulation, Circuit
+from elektron import Line, Dot, Label, Element, Draw, PlotOptions, Simulation, Circuit
 draw = Draw(["/usr/share/kicad/symbols"])
 draw.add(Label("INPUT").rotate(180))
 draw.add(Line())
@@ -76,7 +76,7 @@ draw.add(Element("GND", "power:GND", value="GND", unit=1, on_schema="no").at("U2
 draw.add(Element("GND", "power:+5V", value="+5V", unit=1, on_schema="no").at("U2", "7"))
 
 draw.write("llama.kicad_sch")
-draw.plot("llama.svg", False, 3, "png")
+draw.plot("llama.png", PlotOptions(format="png", scale=3))
 
 print("create circuit")
 circuit = draw.circuit(['/home/etienne/elektron/samples/files/spice/'])
@@ -6,6 +6,7 @@ setup(
     name="elektron-python",
     version="0.1",
     packages=["elektron"],
+    package_data={"elektron": ["themes/*.css"]},
     rust_extensions=[
         RustExtension("elektron.elektron"),
         # RustBin("elektron"),
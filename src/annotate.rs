@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use elektron_sexp::{SchemaElement, Symbol};
+
+use crate::error::Error;
+use crate::{AnchorKind, Draw};
+
+/// get the reference property of the symbol.
+pub fn reference(symbol: &Symbol) -> Option<&str> {
+    symbol
+        .property
+        .iter()
+        .find(|p| p.key == "Reference")
+        .map(|p| p.value.as_str())
+}
+
+/// split the reference in the prefix and the number.
+///
+/// `R12` returns `("R", Some(12))`, `R?` and `R` return `("R", None)`.
+pub fn split_reference(reference: &str) -> (String, Option<u32>) {
+    let prefix = reference.trim_end_matches('?');
+    let prefix = prefix.trim_end_matches(|c: char| c.is_ascii_digit());
+    let number = reference[prefix.len()..].parse::<u32>().ok();
+    (prefix.to_string(), number)
+}
+
+/// get the next free reference with the prefix.
+pub fn next_reference(prefix: &str, references: &[String]) -> String {
+    let mut used: Vec<u32> = references
+        .iter()
+        .filter_map(|r| {
+            let (p, number) = split_reference(r);
+            if p == prefix {
+                number
+            } else {
+                None
+            }
+        })
+        .collect();
+    used.sort_unstable();
+    let mut next = 1;
+    for number in used {
+        if number == next {
+            next += 1;
+        } else if number > next {
+            break;
+        }
+    }
+    if prefix == "#PWR" {
+        format!("#PWR0{:02}", next)
+    } else {
+        format!("{}{}", prefix, next)
+    }
+}
+
+impl Draw {
+    /// get the reference for a new symbol.
+    ///
+    /// references without a number are annotated with the next free number,
+    /// power symbols get a `#PWR` reference. the units after the first one
+    /// take the reference of the last symbol of the library that does not
+    /// have the unit yet.
+    pub(crate) fn annotate_reference(
+        &self,
+        reference: &str,
+        power: bool,
+        lib_id: &str,
+        unit: u32,
+    ) -> String {
+        let (prefix, number) = split_reference(reference);
+        if power && !reference.starts_with('#') {
+            next_reference("#PWR", &self.references())
+        } else if number.is_none() && !prefix.is_empty() {
+            if unit > 1 {
+                if let Some(pending) = self.pending_reference(prefix.as_str(), lib_id, unit) {
+                    return pending;
+                }
+            }
+            next_reference(prefix.as_str(), &self.references())
+        } else {
+            reference.to_string()
+        }
+    }
+
+    /// get the reference of the last symbol with the prefix and library that misses the unit.
+    fn pending_reference(&self, prefix: &str, lib_id: &str, unit: u32) -> Option<String> {
+        let mut symbols: Vec<(String, u32)> = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            if let Ok(iter) = self.schema.iter(page) {
+                for item in iter {
+                    if let SchemaElement::Symbol(symbol) = item {
+                        if symbol.lib_id != lib_id {
+                            continue;
+                        }
+                        if let Some(reference) = reference(symbol) {
+                            if split_reference(reference).0 == prefix {
+                                symbols.push((reference.to_string(), symbol.unit));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        symbols
+            .iter()
+            .rev()
+            .map(|(reference, _)| reference)
+            .find(|reference| !symbols.iter().any(|(r, u)| r == *reference && *u == unit))
+            .cloned()
+    }
+
+    /// collect the references of all symbols in the schema.
+    fn references(&self) -> Vec<String> {
+        let mut references = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            if let Ok(iter) = self.schema.iter(page) {
+                for item in iter {
+                    if let SchemaElement::Symbol(symbol) = item {
+                        if let Some(reference) = reference(symbol) {
+                            references.push(reference.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    /// re-annotate all symbols ordered by position.
+    ///
+    /// with order `xy` the symbols are sorted by column, with `yx` by row.
+    pub(crate) fn annotate_all(&mut self, order: &str) -> Result<(), Error> {
+        if order != "xy" && order != "yx" {
+            return Err(Error::InvalidValue(String::from("order"), order.to_string()));
+        }
+        let mut symbols: Vec<(usize, String, f64, f64)> = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                if let SchemaElement::Symbol(symbol) = item {
+                    if let Some(reference) = reference(symbol) {
+                        symbols.push((page, reference.to_string(), symbol.at[0], symbol.at[1]));
+                    }
+                }
+            }
+        }
+        symbols.sort_by(|a, b| {
+            let (a_pos, b_pos) = if order == "xy" {
+                ((a.2, a.3), (b.2, b.3))
+            } else {
+                ((a.3, a.2), (b.3, b.2))
+            };
+            a.0.cmp(&b.0).then(
+                a_pos
+                    .partial_cmp(&b_pos)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+
+        // the units of a symbol share the reference.
+        let mut counters: HashMap<String, u32> = HashMap::new();
+        let mut mapping: HashMap<String, String> = HashMap::new();
+        for (_, reference, _, _) in &symbols {
+            if mapping.contains_key(reference) {
+                continue;
+            }
+            let (prefix, _) = split_reference(reference);
+            if prefix.is_empty() {
+                continue;
+            }
+            let counter = counters.entry(prefix.to_string()).or_insert(0);
+            *counter += 1;
+            let new_reference = if prefix == "#PWR" {
+                format!("#PWR0{:02}", counter)
+            } else {
+                format!("{}{}", prefix, counter)
+            };
+            mapping.insert(reference.to_string(), new_reference);
+        }
+
+        for page in 0..self.schema.pages.len() {
+            if let Some(page) = self.schema.page(page) {
+                for item in page.items.iter_mut() {
+                    if let SchemaElement::Symbol(symbol) = item {
+                        for property in symbol.property.iter_mut() {
+                            if property.key == "Reference" {
+                                if let Some(new_reference) = mapping.get(&property.value) {
+                                    property.value = new_reference.to_string();
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // the element anchors follow the new references.
+        let mut moved = Vec::new();
+        for (reference, new_reference) in &mapping {
+            if self.anchors.get(reference).map(|a| a.kind) == Some(AnchorKind::Element) {
+                if let Some(anchor) = self.anchors.remove(reference) {
+                    moved.push((new_reference.to_string(), anchor));
+                }
+            }
+        }
+        self.anchors.extend(moved);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_reference, split_reference};
+
+    #[test]
+    fn split() {
+        assert_eq!(split_reference("R12"), (String::from("R"), Some(12)));
+        assert_eq!(split_reference("R?"), (String::from("R"), None));
+        assert_eq!(split_reference("R"), (String::from("R"), None));
+        assert_eq!(split_reference("#PWR01"), (String::from("#PWR"), Some(1)));
+    }
+
+    #[test]
+    fn next_free_number() {
+        let references: Vec<String> = ["R1", "R2", "R4", "C1", "U1", "U1"]
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(next_reference("R", &references), "R3");
+        assert_eq!(next_reference("C", &references), "C2");
+        assert_eq!(next_reference("U", &references), "U2");
+        assert_eq!(next_reference("Q", &references), "Q1");
+        assert_eq!(next_reference("#PWR", &references), "#PWR001");
+    }
+}
@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use elektron_sexp::SchemaElement;
+
+use crate::annotate::{self, split_reference};
+use crate::error::Error;
+use crate::Draw;
+
+/// order the references by prefix and number, `R2` before `R10`.
+fn reference_order(a: &str, b: &str) -> Ordering {
+    let (a_prefix, a_number) = split_reference(a);
+    let (b_prefix, b_number) = split_reference(b);
+    a_prefix.cmp(&b_prefix).then(a_number.cmp(&b_number)).then(a.cmp(b))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// write the rows in the format, the second column is the quantity.
+fn write_bom(format: &str, columns: &[String], rows: &[Vec<String>]) -> Result<String, Error> {
+    let mut out = String::new();
+    match format {
+        "csv" => {
+            for line in std::iter::once(columns).chain(rows.iter().map(|row| row.as_slice())) {
+                let fields: Vec<String> = line.iter().map(|c| csv_field(c)).collect();
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+        "json" => {
+            let lines: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = columns
+                        .iter()
+                        .zip(row)
+                        .enumerate()
+                        .map(|(i, (column, value))| {
+                            // the quantity is a number.
+                            let value = if i == 1 { value.to_string() } else { json_string(value) };
+                            format!("{}: {}", json_string(column), value)
+                        })
+                        .collect();
+                    format!("  {{{}}}", fields.join(", "))
+                })
+                .collect();
+            writeln!(out, "[\n{}\n]", lines.join(",\n"))?;
+        }
+        "html" => {
+            writeln!(out, "<table class=\"bom\">")?;
+            let header: String = columns
+                .iter()
+                .map(|c| format!("<th>{}</th>", html_escape(c)))
+                .collect();
+            writeln!(out, "<tr>{}</tr>", header)?;
+            for row in rows {
+                let cells: String = row
+                    .iter()
+                    .map(|c| format!("<td>{}</td>", html_escape(c)))
+                    .collect();
+                writeln!(out, "<tr>{}</tr>", cells)?;
+            }
+            writeln!(out, "</table>")?;
+        }
+        _ => return Err(Error::InvalidValue(String::from("format"), format.to_string())),
+    }
+    Ok(out)
+}
+
+impl Draw {
+    /// get the bill of materials of the placed symbols.
+    ///
+    /// the units of a symbol are merged to one part and the parts with the
+    /// same `group_by` properties are written in one line. the property
+    /// names are not case sensitive, the extra columns are lower case.
+    /// power symbols and symbols excluded with `in_bom=no` are never
+    /// listed, symbols hidden with `on_schema=no` only when `hidden` is set.
+    pub(crate) fn bill_of_materials(
+        &self,
+        format: &str,
+        group_by: &[String],
+        hidden: bool,
+        exclude: &[String],
+    ) -> Result<String, Error> {
+        let format = format.to_lowercase();
+        let mut parts: Vec<(String, HashMap<String, String>)> = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                if let SchemaElement::Symbol(symbol) = item {
+                    let reference = annotate::reference(symbol).unwrap_or("");
+                    if reference.is_empty()
+                        || reference.starts_with('#')
+                        || !symbol.in_bom
+                        || (!symbol.on_schema && !hidden)
+                        || exclude.iter().any(|e| e == reference)
+                    {
+                        continue;
+                    }
+                    let lib = self
+                        .schema
+                        .get_library(symbol.lib_id.as_str())
+                        .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                    if lib.power {
+                        continue;
+                    }
+                    let index = match parts.iter().position(|(r, _)| r == reference) {
+                        Some(index) => index,
+                        None => {
+                            parts.push((reference.to_string(), HashMap::new()));
+                            parts.len() - 1
+                        }
+                    };
+                    // the other units and properties only add missing values.
+                    for property in &symbol.property {
+                        let value = parts[index].1.entry(property.key.to_lowercase()).or_default();
+                        if value.is_empty() {
+                            *value = property.value.to_string();
+                        }
+                    }
+                }
+            }
+        }
+
+        let group_keys: Vec<String> = group_by.iter().map(|g| g.to_lowercase()).collect();
+        let extra: BTreeSet<&String> = parts
+            .iter()
+            .flat_map(|(_, properties)| properties.keys())
+            .filter(|key| key.as_str() != "reference" && !group_keys.contains(key))
+            .collect();
+
+        let mut groups: Vec<(Vec<String>, Vec<String>, Vec<&HashMap<String, String>>)> =
+            Vec::new();
+        for (reference, properties) in &parts {
+            let key: Vec<String> = group_keys
+                .iter()
+                .map(|g| properties.get(g).cloned().unwrap_or_default())
+                .collect();
+            if let Some((_, references, members)) = groups.iter_mut().find(|(k, _, _)| *k == key) {
+                references.push(reference.to_string());
+                members.push(properties);
+            } else {
+                groups.push((key, vec![reference.to_string()], vec![properties]));
+            }
+        }
+
+        let mut rows: Vec<Vec<String>> = groups
+            .into_iter()
+            .map(|(key, mut references, members)| {
+                references.sort_by(|a, b| reference_order(a, b));
+                let mut row = vec![references.join(", "), references.len().to_string()];
+                row.extend(key);
+                for column in &extra {
+                    // differing values of the group are all listed.
+                    let mut values: Vec<&str> = Vec::new();
+                    for value in members.iter().filter_map(|m| m.get(*column)) {
+                        if !value.is_empty() && !values.contains(&value.as_str()) {
+                            values.push(value);
+                        }
+                    }
+                    row.push(values.join(", "));
+                }
+                row
+            })
+            .collect();
+        let first = |row: &[String]| row[0].split(", ").next().unwrap_or("").to_string();
+        rows.sort_by(|a, b| reference_order(&first(a), &first(b)));
+
+        let mut columns = vec![String::from("Reference"), String::from("Quantity")];
+        columns.extend(group_by.iter().cloned());
+        columns.extend(extra.into_iter().cloned());
+        write_bom(format.as_str(), &columns, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, reference_order, write_bom};
+    use std::cmp::Ordering;
+
+    fn rows() -> (Vec<String>, Vec<Vec<String>>) {
+        let columns = vec![
+            String::from("Reference"),
+            String::from("Quantity"),
+            String::from("value"),
+        ];
+        let rows = vec![
+            vec![String::from("R1, R2"), String::from("2"), String::from("10k")],
+            vec![String::from("C1"), String::from("1"), String::from("1\"<2>")],
+        ];
+        (columns, rows)
+    }
+
+    #[test]
+    fn csv_quotes() {
+        assert_eq!("10k", csv_field("10k"));
+        assert_eq!("\"R1, R2\"", csv_field("R1, R2"));
+        assert_eq!("\"a\"\"b\"", csv_field("a\"b"));
+        assert_eq!("\"a\rb\"", csv_field("a\rb"));
+    }
+
+    #[test]
+    fn order() {
+        assert_eq!(Ordering::Less, reference_order("R2", "R10"));
+        assert_eq!(Ordering::Less, reference_order("C10", "R1"));
+    }
+
+    #[test]
+    fn csv() {
+        let (columns, rows) = rows();
+        assert_eq!(
+            "Reference,Quantity,value\n\"R1, R2\",2,10k\nC1,1,\"1\"\"<2>\"\n",
+            write_bom("csv", &columns, &rows).unwrap()
+        );
+    }
+
+    #[test]
+    fn json() {
+        let (columns, rows) = rows();
+        assert_eq!(
+            "[\n  {\"Reference\": \"R1, R2\", \"Quantity\": 2, \"value\": \"10k\"},\n  \
+             {\"Reference\": \"C1\", \"Quantity\": 1, \"value\": \"1\\\"<2>\"}\n]\n",
+            write_bom("json", &columns, &rows).unwrap()
+        );
+    }
+
+    #[test]
+    fn html() {
+        let (columns, rows) = rows();
+        let html = write_bom("html", &columns, &rows).unwrap();
+        assert!(html.starts_with("<table class=\"bom\">\n<tr><th>Reference</th>"));
+        assert!(html.contains("<tr><td>C1</td><td>1</td><td>1&quot;&lt;2&gt;</td></tr>"));
+    }
+
+    #[test]
+    fn unknown_format() {
+        let (columns, rows) = rows();
+        assert!(write_bom("xls", &columns, &rows).is_err());
+    }
+}
@@ -43,7 +43,7 @@ impl Circuit {
         n: Vec<String>,
         value: String,
     ) -> Result<(), Error> {
-        self.circuit.circuit(reference, n, value).unwrap();
+        self.circuit.circuit(reference, n, value)?;
         Ok(())
     }
     pub fn subcircuit(
@@ -52,18 +52,18 @@ impl Circuit {
         n: Vec<String>,
         circuit: Circuit,
     ) -> Result<(), Error> {
-        self.circuit.subcircuit(name, n, circuit.circuit).unwrap();
+        self.circuit.subcircuit(name, n, circuit.circuit)?;
         Ok(())
     }
     pub fn voltage(&mut self, reference: String, n1: String, n2: String, value: String) {
         self.circuit.voltage(reference, n1, n2, value);
     }
     pub fn save(&self, filename: Option<String>) -> Result<(), Error> {
-        self.circuit.save(filename).unwrap();
+        self.circuit.save(filename)?;
         Ok(())
     }
     pub fn set_value(&mut self, reference: &str, value: &str) -> Result<(), Error> {
-        self.circuit.set_value(reference, value).unwrap();
+        self.circuit.set_value(reference, value)?;
         Ok(())
     }
 }
@@ -83,7 +83,7 @@ impl Simulation {
         }
     }
 
-    pub fn tran(&mut self, py: Python, step: &str, stop: &str, start: &str) -> HashMap<String, Vec<f64>> {
+    pub fn tran(&mut self, py: Python, step: &str, stop: &str, start: &str) -> PyResult<HashMap<String, Vec<f64>>> {
         let res = self.simulation.tran(step, stop, start);
         if let Some(buffer) = &self.simulation.buffer {
             let mut res_string = Vec::new();
@@ -92,12 +92,17 @@ impl Simulation {
                 let line = line.replace('\"', "\\\"");
                 res_string.push(line.replace('\'', "\\\'"));
             }
-            py.eval(format!("print('{}')", res_string.join("\\n")).as_str(), None, None).unwrap();
+            py.eval(format!("print('{}')", res_string.join("\\n")).as_str(), None, None)?;
         }
-        res
+        Ok(res)
     }
 
-    pub fn ac(&mut self, py: Python, start_frequency: &str, stop_frequency: &str, number_of_points: u32,  variation: &str) -> HashMap<String, Vec<f64>> {
+    /// run the operating point analysis.
+    pub fn op(&mut self) -> HashMap<String, Vec<f64>> {
+        self.simulation.op()
+    }
+
+    pub fn ac(&mut self, py: Python, start_frequency: &str, stop_frequency: &str, number_of_points: u32,  variation: &str) -> PyResult<HashMap<String, Vec<f64>>> {
         let res = self.simulation.ac(start_frequency, stop_frequency, number_of_points, variation);
         if let Some(buffer) = &self.simulation.buffer {
             let mut res_string = Vec::new();
@@ -106,8 +111,8 @@ impl Simulation {
                 let line = line.replace('\"', "\\\"");
                 res_string.push(line.replace('\'', "\\\'"));
             }
-            py.eval(format!("print('{}')", res_string.join("\\n")).as_str(), None, None).unwrap();
+            py.eval(format!("print('{}')", res_string.join("\\n")).as_str(), None, None)?;
         }
-        res
+        Ok(res)
     }
 }
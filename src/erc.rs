@@ -0,0 +1,476 @@
+#![allow(clippy::borrow_deref_ref)]
+use std::collections::HashMap;
+
+use elektron_sexp::SchemaElement;
+use pyo3::prelude::*;
+
+use crate::annotate;
+use crate::orientation;
+use crate::error::Error;
+use crate::netlist::sheet_name;
+use crate::Draw;
+
+/// A violation found by the electrical rules check.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// the kind of the violation, for example `unconnected_input`.
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub reference: Option<String>,
+    #[pyo3(get)]
+    pub pin: Option<String>,
+    #[pyo3(get)]
+    pub page: usize,
+    #[pyo3(get)]
+    pub pos: (f64, f64),
+}
+
+#[pymethods]
+impl Violation {
+    fn __repr__(&self) -> String {
+        format!(
+            "Violation({}, {}, ({:.2}, {:.2}))",
+            self.kind, self.message, self.pos.0, self.pos.1
+        )
+    }
+}
+
+/// position key with a resolution of 1/100 mm.
+pub(crate) type Key = (usize, i64, i64);
+
+pub(crate) fn key(page: usize, x: f64, y: f64) -> Key {
+    (page, (x * 100.0).round() as i64, (y * 100.0).round() as i64)
+}
+
+/// A symbol pin with the absolute position.
+pub(crate) struct ErcPin {
+    pub reference: String,
+    pub number: String,
+    pub pin_type: String,
+    pub key: Key,
+    pub pos: (f64, f64),
+}
+
+/// A label with the position, angle and justify of the text.
+pub(crate) struct ErcLabel {
+    pub key: Key,
+    pub text: String,
+    pub angle: f64,
+    pub justify: Vec<String>,
+}
+
+impl ErcLabel {
+    fn new(key: Key, text: &str, angle: f64, justify: &[String]) -> Self {
+        Self { key, text: text.to_string(), angle, justify: justify.to_vec() }
+    }
+}
+
+/// union find for the nets.
+pub(crate) struct Nets {
+    parent: Vec<usize>,
+    nodes: HashMap<Key, usize>,
+}
+
+impl Nets {
+    fn new() -> Self {
+        Self { parent: Vec::new(), nodes: HashMap::new() }
+    }
+    pub fn node(&mut self, key: Key) -> usize {
+        if let Some(node) = self.nodes.get(&key) {
+            *node
+        } else {
+            let node = self.parent.len();
+            self.parent.push(node);
+            self.nodes.insert(key, node);
+            node
+        }
+    }
+    pub fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        self.parent[node] = root;
+        root
+    }
+    pub fn union(&mut self, a: Key, b: Key) {
+        let a = self.node(a);
+        let b = self.node(b);
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a] = b;
+        }
+    }
+}
+
+/// The connectivity of all pages.
+pub(crate) struct Connectivity {
+    pub nets: Nets,
+    pub pins: Vec<ErcPin>,
+    pub wires: Vec<(Key, Key)>,
+    /// the number of wire ends, labels and pins at the position.
+    pub items: HashMap<Key, usize>,
+    pub junctions: Vec<Key>,
+    pub no_connects: Vec<Key>,
+    /// the net names of labels and power symbols, local and hierarchical labels are prefixed with the page.
+    pub names: HashMap<String, Key>,
+    pub pwr_flags: Vec<Key>,
+    /// the position and text of all labels.
+    pub labels: Vec<ErcLabel>,
+}
+
+/// check if the key is on the wire, but not on one of its ends.
+fn on_wire(key: &Key, wire: &(Key, Key)) -> bool {
+    let (start, end) = wire;
+    if key.0 != start.0 || key == start || key == end {
+        return false;
+    }
+    if start.1 == end.1 && key.1 == start.1 {
+        key.2 > start.2.min(end.2) && key.2 < start.2.max(end.2)
+    } else if start.2 == end.2 && key.2 == start.2 {
+        key.1 > start.1.min(end.1) && key.1 < start.1.max(end.1)
+    } else {
+        false
+    }
+}
+
+/// join the wires with the points on them and the sheet pins with the labels of the child pages.
+///
+/// the wires are split at every point that lies on them: junctions, pins,
+/// labels and the ends of other wires. the sheet pins are tuples of the
+/// position, the page of the sheet and the pin name, the hierarchical
+/// labels are connected by name.
+pub(crate) fn connect(
+    nets: &mut Nets,
+    wires: &[(Key, Key)],
+    points: &[Key],
+    sheet_pins: &[(Key, usize, String)],
+    hierarchical: &[(Key, String)],
+) {
+    for wire in wires {
+        for point in points.iter().filter(|point| on_wire(point, wire)) {
+            nets.union(*point, wire.0);
+        }
+    }
+    for (pin, page, name) in sheet_pins {
+        for (label, _) in hierarchical.iter().filter(|(l, n)| l.0 == *page && n == name) {
+            nets.union(*pin, *label);
+        }
+    }
+}
+
+impl Draw {
+    /// collect the nets of all pages.
+    pub(crate) fn connectivity(&self) -> Result<Connectivity, Error> {
+        let mut nets = Nets::new();
+        let mut pins: Vec<ErcPin> = Vec::new();
+        let mut wires: Vec<(Key, Key)> = Vec::new();
+        let mut items: HashMap<Key, usize> = HashMap::new();
+        let mut junctions: Vec<Key> = Vec::new();
+        let mut no_connects: Vec<Key> = Vec::new();
+        let mut names: HashMap<String, Key> = HashMap::new();
+        let mut pwr_flags: Vec<Key> = Vec::new();
+        let mut labels: Vec<ErcLabel> = Vec::new();
+        let mut sheet_pins: Vec<(Key, usize, String)> = Vec::new();
+        let mut hierarchical: Vec<(Key, String)> = Vec::new();
+
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                match item {
+                    SchemaElement::Wire(wire) => {
+                        let start = key(page, wire.pts[[0, 0]], wire.pts[[0, 1]]);
+                        let end = key(page, wire.pts[[1, 0]], wire.pts[[1, 1]]);
+                        nets.union(start, end);
+                        *items.entry(start).or_insert(0) += 1;
+                        *items.entry(end).or_insert(0) += 1;
+                        wires.push((start, end));
+                    }
+                    SchemaElement::Junction(junction) => {
+                        junctions.push(key(page, junction.at[0], junction.at[1]));
+                    }
+                    SchemaElement::NoConnect(nc) => {
+                        no_connects.push(key(page, nc.at[0], nc.at[1]));
+                    }
+                    SchemaElement::Label(label) => {
+                        let at = key(page, label.at[0], label.at[1]);
+                        *items.entry(at).or_insert(0) += 1;
+                        labels.push(ErcLabel::new(at, &label.text, label.angle, &label.effects.justify));
+                        let name = format!("{}/{}", page, label.text);
+                        let other = *names.entry(name).or_insert(at);
+                        nets.union(at, other);
+                    }
+                    SchemaElement::GlobalLabel(label) => {
+                        let at = key(page, label.at[0], label.at[1]);
+                        *items.entry(at).or_insert(0) += 1;
+                        labels.push(ErcLabel::new(at, &label.text, label.angle, &label.effects.justify));
+                        let other = *names.entry(label.text.to_string()).or_insert(at);
+                        nets.union(at, other);
+                    }
+                    SchemaElement::HierarchicalLabel(label) => {
+                        let at = key(page, label.at[0], label.at[1]);
+                        *items.entry(at).or_insert(0) += 1;
+                        labels.push(ErcLabel::new(at, &label.text, label.angle, &label.effects.justify));
+                        names.entry(format!("{}/{}", page, label.text)).or_insert(at);
+                        hierarchical.push((at, label.text.to_string()));
+                    }
+                    SchemaElement::Sheet(sheet) => {
+                        let name = sheet_name(sheet);
+                        let child = self
+                            .pages
+                            .iter()
+                            .position(|info| info.parent == Some(page) && info.name == name);
+                        for pin in &sheet.pin {
+                            let at = key(page, pin.at[0], pin.at[1]);
+                            nets.node(at);
+                            *items.entry(at).or_insert(0) += 1;
+                            if let Some(child) = child {
+                                sheet_pins.push((at, child, pin.name.to_string()));
+                            }
+                        }
+                    }
+                    SchemaElement::Symbol(symbol) => {
+                        let reference = annotate::reference(symbol).unwrap_or("").to_string();
+                        let lib = self
+                            .schema
+                            .get_library(symbol.lib_id.as_str())
+                            .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                        for pin in lib.pins(symbol.unit)? {
+                            let pos = orientation::symbol_transform(symbol, &pin.at)?;
+                            let at = key(page, pos[0], pos[1]);
+                            nets.node(at);
+                            *items.entry(at).or_insert(0) += 1;
+                            if lib.power {
+                                // power symbols connect the nets by value.
+                                let value = symbol
+                                    .property
+                                    .iter()
+                                    .find(|p| p.key == "Value")
+                                    .map(|p| p.value.to_string())
+                                    .unwrap_or_default();
+                                if symbol.lib_id.ends_with(":PWR_FLAG") {
+                                    pwr_flags.push(at);
+                                } else {
+                                    let other = *names.entry(value).or_insert(at);
+                                    nets.union(at, other);
+                                }
+                            }
+                            pins.push(ErcPin {
+                                reference: reference.to_string(),
+                                number: pin.number.0.to_string(),
+                                pin_type: pin.pin_type.to_string(),
+                                key: at,
+                                pos: (pos[0], pos[1]),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut points: Vec<Key> = wires.iter().flat_map(|w| vec![w.0, w.1]).collect();
+        points.extend(junctions.iter());
+        points.extend(pins.iter().map(|pin| pin.key));
+        points.extend(labels.iter().map(|label| label.key));
+        connect(&mut nets, &wires, &points, &sheet_pins, &hierarchical);
+
+        Ok(Connectivity {
+            nets,
+            pins,
+            wires,
+            items,
+            junctions,
+            no_connects,
+            names,
+            pwr_flags,
+            labels,
+        })
+    }
+
+    /// run the electrical rules check on all pages.
+    pub(crate) fn run_erc(&self) -> Result<Vec<Violation>, Error> {
+        let Connectivity {
+            mut nets,
+            pins,
+            wires,
+            items,
+            junctions,
+            no_connects,
+            pwr_flags,
+            ..
+        } = self.connectivity()?;
+        let ends: Vec<Key> = wires.iter().flat_map(|w| vec![w.0, w.1]).collect();
+
+        let mut violations = Vec::new();
+        let pos = |key: &Key| (key.1 as f64 / 100.0, key.2 as f64 / 100.0);
+        for pin in &pins {
+            if pin.pin_type == "no_connect" || no_connects.contains(&pin.key) {
+                continue;
+            }
+            let tapped = wires.iter().find(|w| on_wire(&pin.key, w));
+            if let Some(wire) = tapped {
+                if !junctions.contains(&pin.key) {
+                    violations.push(Violation {
+                        kind: String::from("missing_junction"),
+                        message: format!(
+                            "wire crosses pin {}:{} without a junction",
+                            pin.reference, pin.number
+                        ),
+                        reference: Some(pin.reference.to_string()),
+                        pin: Some(pin.number.to_string()),
+                        page: wire.0 .0,
+                        pos: pin.pos,
+                    });
+                }
+            }
+            // a pin that taps a wire is connected, the junction is checked above.
+            if tapped.is_none() && items.get(&pin.key).copied().unwrap_or(0) <= 1 {
+                let (kind, message) = if pin.pin_type == "input" {
+                    ("unconnected_input", "input pin is not connected")
+                } else {
+                    ("pin_not_connected", "pin is not connected and has no Nc marker")
+                };
+                violations.push(Violation {
+                    kind: kind.to_string(),
+                    message: format!("{} {}:{}", message, pin.reference, pin.number),
+                    reference: Some(pin.reference.to_string()),
+                    pin: Some(pin.number.to_string()),
+                    page: pin.key.0,
+                    pos: pin.pos,
+                });
+            }
+        }
+        for end in &ends {
+            if items.get(end).copied().unwrap_or(0) <= 1
+                && !no_connects.contains(end)
+                && !wires.iter().any(|w| on_wire(end, w))
+            {
+                violations.push(Violation {
+                    kind: String::from("dangling_wire"),
+                    message: String::from("wire end is not connected"),
+                    reference: None,
+                    pin: None,
+                    page: end.0,
+                    pos: pos(end),
+                });
+            }
+        }
+
+        // check the pin types per net.
+        let mut net_pins: HashMap<usize, Vec<&ErcPin>> = HashMap::new();
+        for pin in &pins {
+            let node = nets.node(pin.key);
+            net_pins.entry(nets.find(node)).or_default().push(pin);
+        }
+        let flagged: Vec<usize> = pwr_flags
+            .iter()
+            .map(|flag| {
+                let node = nets.node(*flag);
+                nets.find(node)
+            })
+            .collect();
+        let mut roots: Vec<&usize> = net_pins.keys().collect();
+        roots.sort();
+        for root in roots {
+            let net = &net_pins[root];
+            let outputs: Vec<&&ErcPin> = net.iter().filter(|p| p.pin_type == "output").collect();
+            if outputs.len() > 1 {
+                violations.push(Violation {
+                    kind: String::from("conflicting_outputs"),
+                    message: format!(
+                        "output pins are connected: {}",
+                        outputs.iter().map(|p| format!("{}:{}", p.reference, p.number)).collect::<Vec<String>>().join(", ")
+                    ),
+                    reference: Some(outputs[0].reference.to_string()),
+                    pin: Some(outputs[0].number.to_string()),
+                    page: outputs[0].key.0,
+                    pos: outputs[0].pos,
+                });
+            }
+            let driven = flagged.contains(root) || net.iter().any(|p| p.pin_type == "power_out");
+            if !driven {
+                if let Some(pin) = net.iter().find(|p| p.pin_type == "power_in") {
+                    violations.push(Violation {
+                        kind: String::from("power_not_driven"),
+                        message: format!(
+                            "power input {}:{} is not driven by a power output",
+                            pin.reference, pin.number
+                        ),
+                        reference: Some(pin.reference.to_string()),
+                        pin: Some(pin.number.to_string()),
+                        page: pin.key.0,
+                        pos: pin.pos,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{connect, Key, Nets};
+
+    fn connected(nets: &mut Nets, a: Key, b: Key) -> bool {
+        let (a, b) = (nets.node(a), nets.node(b));
+        nets.find(a) == nets.find(b)
+    }
+
+    #[test]
+    fn t_junction() {
+        let mut nets = Nets::new();
+        let wires = vec![((0, 0, 0), (0, 1000, 0)), ((0, 500, 0), (0, 500, 500))];
+        for wire in &wires {
+            nets.union(wire.0, wire.1);
+        }
+        let points = vec![(0, 0, 0), (0, 1000, 0), (0, 500, 0), (0, 500, 500)];
+        connect(&mut nets, &wires, &points, &[], &[]);
+        assert!(connected(&mut nets, (0, 0, 0), (0, 500, 500)));
+    }
+    #[test]
+    fn crossing_wires() {
+        let mut nets = Nets::new();
+        let wires = vec![((0, 0, 500), (0, 1000, 500)), ((0, 500, 0), (0, 500, 1000))];
+        for wire in &wires {
+            nets.union(wire.0, wire.1);
+        }
+        let ends = vec![(0, 0, 500), (0, 1000, 500), (0, 500, 0), (0, 500, 1000)];
+        connect(&mut nets, &wires, &ends, &[], &[]);
+        assert!(!connected(&mut nets, (0, 0, 500), (0, 500, 0)));
+
+        let mut points = ends;
+        points.push((0, 500, 500));
+        connect(&mut nets, &wires, &points, &[], &[]);
+        assert!(connected(&mut nets, (0, 0, 500), (0, 500, 0)));
+    }
+    #[test]
+    fn pin_on_wire() {
+        let mut nets = Nets::new();
+        let wires = vec![((0, 0, 0), (0, 1000, 0))];
+        nets.union(wires[0].0, wires[0].1);
+        let pin = (0, 250, 0);
+        let other = (1, 250, 0);
+        connect(&mut nets, &wires, &[pin, other], &[], &[]);
+        assert!(connected(&mut nets, pin, (0, 1000, 0)));
+        assert!(!connected(&mut nets, other, (0, 1000, 0)));
+    }
+    #[test]
+    fn sheet_pin() {
+        let mut nets = Nets::new();
+        let sheet_pins = vec![((0, 100, 100), 1, String::from("IN"))];
+        let hierarchical = vec![
+            ((1, 200, 200), String::from("IN")),
+            ((1, 300, 300), String::from("OUT")),
+            ((2, 400, 400), String::from("IN")),
+        ];
+        connect(&mut nets, &[], &[], &sheet_pins, &hierarchical);
+        assert!(connected(&mut nets, (0, 100, 100), (1, 200, 200)));
+        assert!(!connected(&mut nets, (0, 100, 100), (1, 300, 300)));
+        assert!(!connected(&mut nets, (0, 100, 100), (2, 400, 400)));
+    }
+}
@@ -1,5 +1,18 @@
-use pyo3::{PyErr, exceptions::PyOSError};
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyKeyError, PyOSError, PyTypeError, PyValueError},
+    prelude::*,
+    PyErr,
+};
 use elektron_sexp::Error as SexpError;
+use elektron_plot::Error as PlotError;
+use elektron_spice::Error as SpiceError;
+
+create_exception!(elektron, ElektronError, PyException);
+create_exception!(elektron, SymbolNotFoundError, ElektronError);
+create_exception!(elektron, PinNotFoundError, ElektronError);
+create_exception!(elektron, LibraryError, ElektronError);
+create_exception!(elektron, SimulationError, ElektronError);
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Error {
@@ -7,8 +20,8 @@ pub enum Error {
     ParseError,
     #[error("Can not parse: {0}.")]
     SexpError(String),
-    #[error("Pin not found for {0}")]
-    PinNotFound(u32),
+    #[error("Pin {1} not found for {0}")]
+    PinNotFound(String, String),
     #[error("can not find symbol {0}.")]
     SymbolNotFound(String),
     #[error("Library not found {0}.")]
@@ -17,6 +30,8 @@ pub enum Error {
     IoError(String),
     #[error("Can not find Theme item: {0}{1}")]
     Theme(String, String),
+    #[error("Can not parse theme {0}: {1}")]
+    ThemeSyntax(String, String),
     #[error("Spice model not found: {0}")]
     SpiceModelNotFound(String),
     #[error("Unknown circuit element {0}")]
@@ -27,6 +42,34 @@ pub enum Error {
     PropertyNotFound(String, String),
     #[error("Library \"{0}\" not found in schema")]
     LinraryNotFound(String),
+    #[error("Item not found {0}")]
+    UnknownItem(String),
+    #[error("unknown type for {0}: {1}")]
+    UnknownType(String, String),
+    #[error("invalid value for {0}: {1}")]
+    InvalidValue(String, String),
+    #[error("tox and toy can only be used on symbols with two pins: {0}")]
+    TwoPinsExpected(String),
+    #[error("the pins of {0} are not aligned with the tox or toy direction")]
+    NotAligned(String),
+    #[error("the tox or toy target of {0} is closer than the length of the element")]
+    StretchTooShort(String),
+    #[error("Simulation error: {0}")]
+    Simulation(String),
+    #[error("No route found from {0} to {1}")]
+    NoRoute(String, String),
+    #[error("Anchor not found: {0}")]
+    AnchorNotFound(String),
+    #[error("Anchor already exists: {0}")]
+    DuplicateAnchor(String),
+    #[error("Page {0} not found")]
+    PageNotFound(usize),
+    #[error("Net not found: {0}")]
+    NetNotFound(String),
+    #[error("Can not plot schema: {0}")]
+    Plot(String),
+    #[error("the grid must be a positive number: {0}")]
+    InvalidGrid(f64),
 }
 
 impl std::convert::From<std::io::Error> for Error {
@@ -41,7 +84,36 @@ impl std::convert::From<std::fmt::Error> for Error {
 }
 impl std::convert::From<Error> for PyErr {
     fn from(err: Error) -> PyErr {
-        PyOSError::new_err(err.to_string())
+        let message = err.to_string();
+        match err {
+            Error::PinNotFound(reference, pin) => with_attributes(
+                PinNotFoundError::new_err(message),
+                &[("reference", reference), ("pin", pin)],
+            ),
+            Error::SymbolNotFound(reference) => with_attributes(
+                SymbolNotFoundError::new_err(message),
+                &[("reference", reference)],
+            ),
+            Error::NoPinsFound(reference, _)
+            | Error::TwoPinsExpected(reference)
+            | Error::NotAligned(reference)
+            | Error::StretchTooShort(reference) => {
+                with_attributes(ElektronError::new_err(message), &[("reference", reference)])
+            }
+            Error::LibraryNotFound(library) | Error::LinraryNotFound(library) => {
+                with_attributes(LibraryError::new_err(message), &[("library", library)])
+            }
+            Error::SpiceModelNotFound(_)
+            | Error::UnknownCircuitElement(_)
+            | Error::Simulation(_) => SimulationError::new_err(message),
+            Error::UnknownItem(_) | Error::UnknownType(_, _) => PyTypeError::new_err(message),
+            Error::IoError(_) => PyOSError::new_err(message),
+            Error::AnchorNotFound(_) => PyKeyError::new_err(message),
+            Error::InvalidGrid(_) | Error::InvalidValue(_, _) | Error::DuplicateAnchor(_) => {
+                PyValueError::new_err(message)
+            }
+            _ => ElektronError::new_err(message),
+        }
     }
 }
 impl std::convert::From<SexpError> for Error {
@@ -49,3 +121,39 @@ impl std::convert::From<SexpError> for Error {
         Error::SexpError(err.to_string())
     }
 }
+impl std::convert::From<SpiceError> for Error {
+    fn from(err: SpiceError) -> Error {
+        Error::Simulation(err.to_string())
+    }
+}
+impl std::convert::From<PlotError> for Error {
+    fn from(err: PlotError) -> Error {
+        Error::Plot(err.to_string())
+    }
+}
+impl std::convert::From<lopdf::Error> for Error {
+    fn from(err: lopdf::Error) -> Error {
+        Error::Plot(err.to_string())
+    }
+}
+
+/// set the attributes on the python exception value.
+fn with_attributes(err: PyErr, attributes: &[(&str, String)]) -> PyErr {
+    Python::with_gil(|py| {
+        let value = err.value(py);
+        for (key, attribute) in attributes {
+            value.setattr(*key, attribute).ok();
+        }
+    });
+    err
+}
+
+/// register the exception classes in the python module.
+pub fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("ElektronError", py.get_type::<ElektronError>())?;
+    m.add("SymbolNotFoundError", py.get_type::<SymbolNotFoundError>())?;
+    m.add("PinNotFoundError", py.get_type::<PinNotFoundError>())?;
+    m.add("LibraryError", py.get_type::<LibraryError>())?;
+    m.add("SimulationError", py.get_type::<SimulationError>())?;
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use elektron_sexp::Symbol;
+use ndarray::{arr1, Array, Array1, Array2, Axis, Dimension};
+
+use crate::error::Error;
+use crate::{annotate, orientation, Draw};
+
+/// the default grid of 50 mil.
+pub const DEFAULT_GRID: f64 = 1.27;
+/// positions closer to the grid than this are on the grid.
+const GRID_TOLERANCE: f64 = 0.001;
+
+/// check if the value is on the grid.
+pub fn on_grid(grid: f64, value: f64) -> bool {
+    (value - (value / grid).round() * grid).abs() < GRID_TOLERANCE
+}
+
+/// the position where a drawing starts, (10, 10) moved to the grid.
+pub fn start_pos(grid: f64) -> Array1<f64> {
+    let start = ((10.0 / grid).round() * grid * 10000.0).round() / 10000.0;
+    arr1(&[start, start])
+}
+
+/// check that the grid is a positive number.
+pub fn check_grid(grid: f64) -> Result<f64, Error> {
+    if grid.is_finite() && grid > 0.0 {
+        Ok(grid)
+    } else {
+        Err(Error::InvalidGrid(grid))
+    }
+}
+
+impl Draw {
+    /// snap the coordinates to the grid.
+    pub(crate) fn snap<D: Dimension>(&self, value: Array<f64, D>) -> Array<f64, D> {
+        let grid = self.grid;
+        value.mapv_into(|v| ((v / grid).round() * grid * 10000.0).round() / 10000.0)
+    }
+
+    /// snap the position to the grid and warn when it was moved.
+    pub(crate) fn snap_pos(&mut self, item: &str, pos: Array1<f64>) -> Array1<f64> {
+        let snapped = self.snap(pos.clone());
+        if !on_grid(self.grid, pos[0]) || !on_grid(self.grid, pos[1]) {
+            self.warnings.push(format!(
+                "{} moved to grid: ({:.2}, {:.2}) -> ({:.2}, {:.2})",
+                item, pos[0], pos[1], snapped[0], snapped[1]
+            ));
+        }
+        snapped
+    }
+
+    /// snap all points to the grid and warn for the points that were moved.
+    pub(crate) fn snap_pts(&mut self, item: &str, pts: Array2<f64>) -> Array2<f64> {
+        let mut snapped = pts.clone();
+        for (i, row) in pts.axis_iter(Axis(0)).enumerate() {
+            let pos = self.snap_pos(item, row.to_owned());
+            snapped.row_mut(i).assign(&pos);
+        }
+        snapped
+    }
+
+    /// warn for pins of the symbol that are off grid.
+    pub(crate) fn check_pins(&mut self, symbol: &Symbol) -> Result<(), Error> {
+        let lib = self.get_library(symbol.lib_id.as_str())?;
+        let reference = annotate::reference(symbol).unwrap_or("").to_string();
+        for pin in lib.pins(symbol.unit)? {
+            let pos = orientation::symbol_transform(symbol, &pin.at)?;
+            if !on_grid(self.grid, pos[0]) || !on_grid(self.grid, pos[1]) {
+                self.warnings.push(format!(
+                    "{} pin {} is off grid: ({:.2}, {:.2})",
+                    reference, pin.number.0, pos[0], pos[1]
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_grid, on_grid, start_pos};
+
+    #[test]
+    fn grid_values() {
+        assert_eq!(check_grid(1.27).unwrap(), 1.27);
+        assert!(check_grid(0.0).is_err());
+        assert!(check_grid(-1.27).is_err());
+        assert!(check_grid(f64::NAN).is_err());
+        assert!(check_grid(f64::INFINITY).is_err());
+    }
+    #[test]
+    fn values_on_grid() {
+        assert!(on_grid(1.27, 2.54));
+        assert!(on_grid(1.27, -3.81));
+        assert!(!on_grid(1.27, 2.0));
+    }
+    #[test]
+    fn start_on_grid() {
+        for grid in [1.27, 2.54, 2.0, 0.635] {
+            let start = start_pos(grid);
+            assert!(on_grid(grid, start[0]) && on_grid(grid, start[1]));
+        }
+        assert!((start_pos(1.27)[0] - 10.16).abs() < 1e-9);
+        assert_eq!(10.0, start_pos(2.0)[0]);
+    }
+}
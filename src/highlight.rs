@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use elektron_spice::Netlist;
+use ndarray::{arr1, Array1};
+use pyo3::prelude::*;
+
+use crate::erc::{Connectivity, Key};
+use crate::error::Error;
+use crate::netlist::node_name;
+use crate::placement::text_box;
+use crate::Draw;
+
+/// the colors of the highlighted nets.
+const COLORS: [&str; 6] = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4"];
+/// the line width of highlighted wires.
+const HIGHLIGHT_WIDTH: f64 = 0.4;
+
+/// A net to highlight, by name or by a pin on the net.
+#[derive(Debug, Clone)]
+pub enum NetSeed {
+    Name(String),
+    Pin(String, String),
+}
+
+impl NetSeed {
+    /// get the seed from a net name or a `(reference, pin)` tuple.
+    pub fn extract(item: &'_ PyAny) -> PyResult<Self> {
+        if let Ok(name) = item.extract::<String>() {
+            return Ok(NetSeed::Name(name));
+        }
+        if let Ok((reference, pin)) = item.extract::<(String, String)>() {
+            return Ok(NetSeed::Pin(reference, pin));
+        }
+        Err(Error::UnknownType(String::from("highlight"), item.to_string()).into())
+    }
+}
+
+fn pos(key: &Key) -> Array1<f64> {
+    arr1(&[key.1 as f64 / 100.0, key.2 as f64 / 100.0])
+}
+
+/// The nets to highlight with the geometry of the pages.
+///
+/// the net of an item is looked up in the netlist by its position.
+pub(crate) struct Highlight {
+    connectivity: Connectivity,
+    netlist: Netlist,
+    nets: Vec<String>,
+}
+
+impl Highlight {
+    /// get the color of the net at the position.
+    fn color(&self, key: &Key) -> Option<&'static str> {
+        let name = node_name(&self.netlist, key.0, &pos(key))?;
+        self.nets.iter().position(|n| *n == name).map(|i| COLORS[i % COLORS.len()])
+    }
+}
+
+impl Draw {
+    /// get the nets of the seeds.
+    ///
+    /// the seeds are located on the pages, the nets are taken from the netlist.
+    pub(crate) fn highlighted_nets(&self, seeds: &[NetSeed]) -> Result<Highlight, Error> {
+        let connectivity = self.connectivity()?;
+        let netlist = self.paged_netlist()?;
+        let mut nets = Vec::new();
+        for seed in seeds {
+            let key = match seed {
+                NetSeed::Name(name) => connectivity
+                    .names
+                    .get(name)
+                    .or_else(|| {
+                        (0..self.schema.pages.len())
+                            .find_map(|page| connectivity.names.get(&format!("{}/{}", page, name)))
+                    })
+                    .copied()
+                    .ok_or_else(|| Error::NetNotFound(name.to_string()))?,
+                NetSeed::Pin(reference, number) => connectivity
+                    .pins
+                    .iter()
+                    .find(|p| &p.reference == reference && &p.number == number)
+                    .map(|p| p.key)
+                    .ok_or_else(|| Error::PinNotFound(reference.to_string(), number.to_string()))?,
+            };
+            let net = node_name(&netlist, key.0, &pos(&key)).ok_or_else(|| match seed {
+                NetSeed::Name(name) => Error::NetNotFound(name.to_string()),
+                NetSeed::Pin(reference, number) => {
+                    Error::NetNotFound(format!("{}:{}", reference, number))
+                }
+            })?;
+            nets.push(net);
+        }
+        Ok(Highlight { connectivity, netlist, nets })
+    }
+}
+
+/// get the svg elements that draw the nets of the page in the highlight colors.
+pub(crate) fn highlight_svg(highlight: &Highlight, page: usize) -> Result<String, Error> {
+    let mut svg = String::new();
+    let connectivity = &highlight.connectivity;
+    for (start, end) in connectivity.wires.iter().filter(|w| w.0 .0 == page) {
+        if let Some(color) = highlight.color(start) {
+            let (a, b) = (pos(start), pos(end));
+            write!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\"/>",
+                a[0], a[1], b[0], b[1], color, HIGHLIGHT_WIDTH
+            )?;
+        }
+    }
+    let points = connectivity
+        .junctions
+        .iter()
+        .chain(connectivity.pins.iter().map(|p| &p.key))
+        .filter(|k| k.0 == page);
+    for key in points {
+        if let Some(color) = highlight.color(key) {
+            let at = pos(key);
+            write!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"0.6\" fill=\"{}\"/>", at[0], at[1], color)?;
+        }
+    }
+    for label in connectivity.labels.iter().filter(|l| l.key.0 == page) {
+        if let Some(color) = highlight.color(&label.key) {
+            let rect = text_box(&pos(&label.key), &label.text, &label.justify, label.angle);
+            write!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"0.2\"/>",
+                rect[0],
+                rect[1],
+                rect[2] - rect[0],
+                rect[3] - rect[1],
+                color
+            )?;
+        }
+    }
+    Ok(svg)
+}
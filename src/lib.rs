@@ -1,35 +1,21 @@
 #![allow(clippy::borrow_deref_ref)]
-use lazy_static::lazy_static;
 use crate::circuit::Circuit;
 use crate::error::Error;
 use elektron_sexp::{
      Effects, Junction, Label, LibrarySymbol, Property, SchemaElement, Stroke, Symbol, Wire,
-     uuid, Bounds, Library, Schema, Shape, Transform, NoConnect,
+     uuid, Bounds, Library, Schema, NoConnect, Sheet, SheetPin,
+     GlobalLabel, HierarchicalLabel, Bus, BusEntry, Text, Polyline,
 };
-use elektron_plot as plot;
-use itertools::Itertools;
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 use std::collections::HashMap;
-use std::env::temp_dir;
-use std::{fs::{File, self}, io::Read};
+use std::path::Path;
 use uuid::Uuid;
 
 use ndarray::{arr1, arr2, Array1, Array2};
-use rand::Rng;
-
-mod model;
-mod error;
-mod circuit;
 
 const LABEL_BORDER: f64 = 2.54;
-
-lazy_static! {
-    pub static ref MIRROR: HashMap<String, Array2<f64>> = HashMap::from([ //TODO make global
-        (String::from(""), arr2(&[[1., 0.], [0., -1.]])),
-        (String::from("x"), arr2(&[[1., 0.], [0., 1.]])),
-        (String::from("y"), arr2(&[[-1., 0.], [0., -1.]])),
-    ]);
-}
+const SHEET_PIN_SPACING: f64 = 2.54;
 
 macro_rules! uuid {
     () => {
@@ -39,48 +25,206 @@ macro_rules! uuid {
 
 macro_rules! round {
     ($val: expr) => {
-        $val.mapv_into(|v| format!("{:.2}", v).parse::<f64>().unwrap())
+        $val.mapv_into(|v| (v * 100.0).round() / 100.0)
     };
 }
 
-fn filter_properties(node: &&mut Property) -> bool {
-    if let Some(effects) = &node.effects {
-        !effects.hide
-    } else {
-        true
+mod model;
+mod error;
+mod circuit;
+mod annotate;
+mod erc;
+mod query;
+mod route;
+mod grid;
+mod orientation;
+mod placement;
+mod unit;
+mod plotting;
+mod theme;
+mod highlight;
+mod op;
+mod pdf;
+mod bom;
+mod netlist;
+
+#[pyclass]
+pub struct Draw {
+    schema: Schema,
+    libs: Library,
+    last_pos: Array1<f64>,
+    page: usize,
+    grid: f64,
+    warnings: Vec<String>,
+    direction: model::Direction,
+    anchors: HashMap<String, Anchor>,
+    bus_aliases: HashMap<String, Vec<String>>,
+    pages: Vec<PageInfo>,
+}
+
+/// The kind of item that created an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnchorKind {
+    Dot,
+    Label,
+    Element,
+}
+
+/// A named position on a page.
+#[derive(Debug, Clone)]
+struct Anchor {
+    kind: AnchorKind,
+    page: usize,
+    pos: Array1<f64>,
+}
+
+/// The sheet of a page, the root page has no parent.
+#[derive(Debug, Clone)]
+struct PageInfo {
+    name: String,
+    file: String,
+    parent: Option<usize>,
+}
+
+impl PageInfo {
+    fn root(name: &str) -> Self {
+        Self { name: name.to_string(), file: format!("{}.kicad_sch", name), parent: None }
     }
 }
 
-fn sort_properties(a: &&mut Property, b: &&mut Property) -> std::cmp::Ordering {
-    a.id.cmp(&b.id)
+/// Context returned by `Draw.sheet`, restores the parent page on exit.
+#[pyclass]
+pub struct SheetContext {
+    draw: Py<Draw>,
+    page: usize,
+    last_pos: Array1<f64>,
 }
 
+#[pymethods]
+impl SheetContext {
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: &PyAny,
+        _exc_value: &PyAny,
+        _traceback: &PyAny,
+    ) -> bool {
+        let mut draw = self.draw.borrow_mut(py);
+        draw.page = self.page;
+        draw.last_pos = self.last_pos.clone();
+        false
+    }
+}
+
+/// Context returned by `Draw.branch`, restores the page, position and direction on exit.
 #[pyclass]
-pub struct Draw {
-    schema: Schema,
-    libs: Library,
+pub struct BranchContext {
+    draw: Py<Draw>,
+    page: usize,
     last_pos: Array1<f64>,
+    direction: model::Direction,
+}
+
+#[pymethods]
+impl BranchContext {
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: &PyAny,
+        _exc_value: &PyAny,
+        _traceback: &PyAny,
+    ) -> bool {
+        let mut draw = self.draw.borrow_mut(py);
+        draw.page = self.page;
+        draw.last_pos = self.last_pos.clone();
+        draw.direction = self.direction.clone();
+        false
+    }
 }
 
 #[pymethods]
 impl Draw {
     #[new]
-    pub fn new(library_path: Vec<String>) -> Self {
+    #[args(grid = "grid::DEFAULT_GRID")]
+    pub fn new(library_path: Vec<String>, grid: f64) -> Result<Self, Error> {
+        let grid = grid::check_grid(grid)?;
         let mut schema = Schema::new();
         schema.new_page();
-        Self {
+        Ok(Self {
             schema,
             libs: Library::new(library_path),
-            last_pos: arr1(&[10.0, 10.0]),
+            last_pos: grid::start_pos(grid),
+            page: 0,
+            grid,
+            warnings: Vec::new(),
+            direction: model::Direction::Right,
+            anchors: HashMap::new(),
+            bus_aliases: HashMap::new(),
+            pages: vec![PageInfo::root("root")],
+        })
+    }
+
+    /// open an existing schema for further editing.
+    ///
+    /// the files of the child sheets are loaded from the directory of the schema.
+    #[staticmethod]
+    #[args(grid = "grid::DEFAULT_GRID")]
+    pub fn open(path: &str, library_path: Vec<String>, grid: f64) -> Result<Self, Error> {
+        let grid = grid::check_grid(grid)?;
+        let mut schema = Schema::load(path)?;
+        let root = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("root"));
+        if schema.pages.is_empty() {
+            schema.new_page();
         }
+        let mut draw = Self {
+            schema,
+            libs: Library::new(library_path),
+            last_pos: grid::start_pos(grid),
+            page: 0,
+            grid,
+            warnings: Vec::new(),
+            direction: model::Direction::Right,
+            anchors: HashMap::new(),
+            bus_aliases: HashMap::new(),
+            pages: vec![PageInfo::root(root.as_str())],
+        };
+        if draw.schema.pages.len() == 1 {
+            let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+            draw.load_sheets(dir, 0)?;
+        }
+        draw.load_anchors()?;
+        Ok(draw)
     }
 
     fn add(&mut self, item: &'_ PyAny) -> PyResult<()> {
+        // a bus is also a line, it has to be checked first.
+        let bus: PyResult<PyRef<model::Bus>> = item.extract();
+        if let Ok(bus) = bus {
+            let line: &model::Line = bus.as_ref();
+            self.add_bus(line.clone())?;
+            return Ok(());
+        }
         let line: Result<model::Line, PyErr> = item.extract();
         if let Ok(line) = line {
             self.add_line(line)?;
             return Ok(());
         }
+        let entry: PyResult<PyRefMut<model::BusEntry>> = item.extract();
+        if let Ok(mut entry) = entry {
+            self.add_bus_entry(&mut entry)?;
+            return Ok(());
+        }
         let dot: PyResult<PyRefMut<model::Dot>> = item.extract();
         if let Ok(mut dot) = dot {
             if dot.pos == vec![0.0, 0.0] {
@@ -103,75 +247,533 @@ impl Draw {
             self.add_label(label)?;
             return Ok(());
         }
-        let element: Result<model::Element, PyErr> = item.extract();
-        if let Ok(element) = element {
-            self.add_symbol(element)?;
+        let label: Result<model::GlobalLabel, PyErr> = item.extract();
+        if let Ok(label) = label {
+            self.add_global_label(label)?;
+            return Ok(());
+        }
+        let label: Result<model::HierarchicalLabel, PyErr> = item.extract();
+        if let Ok(label) = label {
+            self.add_hierarchical_label(label)?;
+            return Ok(());
+        }
+        let element: PyResult<PyRefMut<model::Element>> = item.extract();
+        if let Ok(mut element) = element {
+            let reference = self.add_symbol(element.clone())?;
+            element.reference = reference;
             return Ok(());
         }
-        panic!("Item not found {:?}", item);
+        let text: Result<model::Text, PyErr> = item.extract();
+        if let Ok(text) = text {
+            self.add_text(text)?;
+            return Ok(());
+        }
+        let polyline: Result<model::Polyline, PyErr> = item.extract();
+        if let Ok(polyline) = polyline {
+            let pts = polyline.pts.iter().map(|p| unit::pos_mm(p, self.grid)).collect();
+            self.add_polyline(pts, polyline.width, polyline.stroke)?;
+            return Ok(());
+        }
+        let rectangle: Result<model::Rectangle, PyErr> = item.extract();
+        if let Ok(rect) = rectangle {
+            // kicad 6 has no rectangle in the schema, draw a closed polyline.
+            let start = unit::pos_mm(&rect.start, self.grid);
+            let end = unit::pos_mm(&rect.end, self.grid);
+            let pts = vec![
+                (start.0, start.1),
+                (end.0, start.1),
+                (end.0, end.1),
+                (start.0, end.1),
+                (start.0, start.1),
+            ];
+            self.add_polyline(pts, rect.width, rect.stroke)?;
+            return Ok(());
+        }
+        Err(Error::UnknownItem(item.to_string()).into())
+    }
+
+    /// create a child sheet and draw the following items on its page.
+    ///
+    /// the sheet symbol with the sheet pins is placed on the current page.
+    pub fn sheet(mut slf: PyRefMut<'_, Self>, sheet: &'_ PyAny) -> PyResult<SheetContext> {
+        let sheet: model::Sheet = if let Ok(name) = sheet.extract::<String>() {
+            model::Sheet::new(name, None)
+        } else {
+            sheet.extract()?
+        };
+        let parent = slf.page;
+        let last_pos = slf.last_pos.clone();
+        let info = PageInfo {
+            name: sheet.name.to_string(),
+            file: sheet.filename.to_string(),
+            parent: Some(parent),
+        };
+        slf.add_sheet(sheet)?;
+        slf.schema.new_page();
+        slf.page = slf.schema.pages.len() - 1;
+        slf.pages.push(info);
+        slf.last_pos = grid::start_pos(slf.grid);
+        Ok(SheetContext {
+            draw: slf.into(),
+            page: parent,
+            last_pos,
+        })
+    }
+
+    /// draw a side branch and return to the current position on exit.
+    ///
+    /// the direction is used for lines without a direction inside the branch.
+    pub fn branch(mut slf: PyRefMut<'_, Self>, direction: Option<&str>) -> PyResult<BranchContext> {
+        let page = slf.page;
+        let last_pos = slf.last_pos.clone();
+        let current = slf.direction.clone();
+        if let Some(direction) = direction {
+            slf.direction = model::Direction::from(direction)?;
+        }
+        Ok(BranchContext {
+            draw: slf.into(),
+            page,
+            last_pos,
+            direction: current,
+        })
     }
 
+    /// continue drawing at the dot, the pin or the position.
+    pub fn goto(&mut self, reference: &'_ PyAny, pin: Option<&'_ PyAny>) -> PyResult<()> {
+        if let Some(dot) = model::anchor(reference, pin) {
+            self.last_pos = self.dot_pos(&dot)?;
+            if let Some(anchor) = dot.name.as_ref().and_then(|name| self.anchors.get(name)) {
+                self.page = anchor.page;
+            }
+            return Ok(());
+        }
+        if let Some(pos) = unit::extract_pos(reference) {
+            let pos = unit::pos_mm(&pos, self.grid);
+            self.last_pos = arr1(&[pos.0, pos.1]);
+            return Ok(());
+        }
+        if let Some(pin) = pin {
+            let reference: Result<String, PyErr> = reference.extract();
+            let pin: Result<String, PyErr> = pin.extract();
+            if let (Ok(reference), Ok(pin)) = (reference, pin) {
+                if let Some(anchor) = self.anchors.get(&reference) {
+                    self.page = anchor.page;
+                }
+                self.last_pos = self.pin_pos(reference, pin)?;
+                return Ok(());
+            }
+        }
+        Err(Error::UnknownType(String::from("goto"), reference.to_string()).into())
+    }
+
+    /// define a bus alias, labels with the alias name carry the members.
+    pub fn bus_alias(&mut self, name: &str, members: Vec<String>) -> Result<(), Error> {
+        for member in &members {
+            model::expand_bus(member, &HashMap::new())?;
+        }
+        self.bus_aliases.insert(name.to_string(), members);
+        Ok(())
+    }
+
+    /// get the members of a bus label, bus aliases are expanded.
+    pub fn bus_members(&self, name: &str) -> Result<Vec<String>, Error> {
+        model::expand_bus(name, &self.bus_aliases)
+    }
+
+    /// get the named dot, label or element as a dot.
+    pub fn anchor(&self, name: &str) -> Result<model::Dot, Error> {
+        let pos = &self
+            .anchors
+            .get(name)
+            .ok_or_else(|| Error::AnchorNotFound(name.to_string()))?
+            .pos;
+        let mut dot = model::Dot::new(Some(name.to_string()));
+        dot.pos = vec![pos[0], pos[1]];
+        Ok(dot)
+    }
+
+    fn __getitem__(&self, name: &str) -> Result<model::Dot, Error> {
+        self.anchor(name)
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.anchors.contains_key(name)
+    }
+
+    /// the direction of lines without a direction.
+    #[getter]
+    pub fn direction(&self) -> &str {
+        self.direction.name()
+    }
+
+    #[setter]
+    pub fn set_direction(&mut self, direction: &str) -> Result<(), Error> {
+        self.direction = model::Direction::from(direction)?;
+        Ok(())
+    }
+
+    /// re-annotate the references of all symbols by position.
+    #[args(order = "\"xy\"")]
+    pub fn annotate(&mut self, order: &str) -> Result<(), Error> {
+        self.annotate_all(order)
+    }
+
+    /// run the electrical rules check and return the violations.
+    pub fn erc(&self) -> Result<Vec<erc::Violation>, Error> {
+        self.run_erc()
+    }
+
+    /// get all symbols placed in the schema.
+    pub fn symbols(&self) -> Result<Vec<query::PlacedSymbol>, Error> {
+        self.placed_symbols()
+    }
+
+    /// get the symbol by reference and unit.
+    #[args(unit = "1")]
+    pub fn symbol(&self, reference: &str, unit: u32) -> Result<query::PlacedSymbol, Error> {
+        self.placed_symbol(reference, unit)
+    }
+
+    /// get the pins of the symbol with the absolute positions.
+    pub fn pins(&self, reference: &str) -> Result<Vec<query::PlacedPin>, Error> {
+        self.placed_pins(reference)
+    }
+
+    /// get the bill of materials as csv, json or html.
+    ///
+    /// the parts are grouped by the `group_by` properties, the references
+    /// in `exclude` are not listed.
+    #[args(format = "\"csv\"", hidden = "false")]
+    pub fn bom(
+        &self,
+        format: &str,
+        group_by: Option<Vec<String>>,
+        hidden: bool,
+        exclude: Option<Vec<String>>,
+    ) -> Result<String, Error> {
+        let group_by =
+            group_by.unwrap_or_else(|| vec![String::from("value"), String::from("footprint")]);
+        self.bill_of_materials(format, &group_by, hidden, &exclude.unwrap_or_default())
+    }
+
+    /// connect two pins with an orthogonal wire.
+    ///
+    /// the pins are given as `(reference, pin)`.
+    pub fn connect(&mut self, start: (String, String), end: (String, String)) -> Result<(), Error> {
+        self.connect_pins(start, end)
+    }
+
+    /// the grid all positions are snapped to.
+    #[getter]
+    pub fn grid(&self) -> f64 {
+        self.grid
+    }
+
+    #[setter]
+    pub fn set_grid(&mut self, grid: f64) -> Result<(), Error> {
+        self.grid = grid::check_grid(grid)?;
+        Ok(())
+    }
+
+    /// one grid unit in mm.
+    #[getter]
+    pub fn unit(&self) -> f64 {
+        self.grid
+    }
+
+    /// items that ended up off grid.
+    #[getter]
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    /// write the schema, the child pages are written to their sheet files
+    /// in the same directory.
     pub fn write(&mut self, filename: &str) -> Result<(), Error> {
-        self.schema.write(filename).unwrap(); //TODO convert error
+        if self.schema.pages.len() == 1 {
+            self.schema.write(filename)?;
+            return Ok(());
+        }
+        let dir = Path::new(filename).parent().unwrap_or_else(|| Path::new(""));
+        for (page, info) in self.pages.iter().enumerate() {
+            let mut schema = Schema::new();
+            let content = self.schema.pages.get(page).ok_or(Error::PageNotFound(page))?;
+            schema.pages.push(content.clone());
+            let path = if info.parent.is_none() {
+                filename.to_string()
+            } else {
+                dir.join(&info.file).to_string_lossy().to_string()
+            };
+            schema.write(path.as_str())?;
+        }
         Ok(())
     }
 
-    #[args(netlist=false)]
+    /// plot the schema and return the page names with the images.
+    ///
+    /// the pages are also written when a filename is given, the format
+    /// is then taken from the file extension when no options are set.
     pub fn plot(
-        &mut self,
+        &self,
+        py: Python,
         filename: Option<&str>,
-        border: bool,
-        scale: f64,
-        imagetype: &str,
-        netlist: bool,
-    ) -> Result<Option<Vec<Vec<u8>>>, Error> {
-        let theme = if let Ok(theme) = std::env::var("ELEKTRON_THEME") {
-            theme
-        } else {
-            String::from("kicad_2000")
+        options: Option<plotting::PlotOptions>,
+    ) -> PyResult<Vec<(String, PyObject)>> {
+        let options = match (options, filename) {
+            (Some(options), _) => options,
+            (None, Some(filename)) => plotting::PlotOptions::for_file(filename)?,
+            (None, None) => plotting::PlotOptions::from_env()?,
         };
-        let netlist = if netlist { 
-            Some(elektron_spice::Netlist::from(&self.schema).unwrap())
-        } else { None };
-
+        let pages = self.plot_pages(&options)?;
         if let Some(filename) = filename {
-            plot::plot_schema(&self.schema, None, scale, border, theme.as_str(), netlist, Some(imagetype)).unwrap();
-            Ok(None)
-        } else {
-            /* let mut rng = rand::thread_rng();
-            let num: u32 = rng.gen();
-            let filename =
-                String::new() + temp_dir().to_str().unwrap() + "/" + &num.to_string() + "." + imagetype; */
-            let res = plot::plot_schema_buffer(&self.schema, scale, border, theme.as_str(), netlist, imagetype).unwrap();
-            
-            /* let mut f = File::open(&filename).expect("no file found");
-            let metadata = fs::metadata(&filename).expect("unable to read metadata");
-            let mut buffer = vec![0; metadata.len() as usize];
-            f.read_exact(&mut buffer).expect("buffer overflow"); */
-            Ok(Some(res))
-            //print_from_file(&filename, &Config::default()).unwrap();
-        }
-    }
-
-    pub fn circuit(&mut self, pathlist: Vec<String>) -> Circuit {
-        let netlist = elektron_spice::Netlist::from(&self.schema).unwrap();
+            self.write_pages(filename, &pages)?;
+        }
+        Ok(pages
+            .into_iter()
+            .map(|(name, buffer)| (name, PyBytes::new(py, &buffer).into()))
+            .collect())
+    }
+
+    /// write all pages with the page titles and the title block to one pdf file.
+    ///
+    /// theme files can not be used for the pdf, use one of the built in themes.
+    pub fn export_pdf(&self, path: &str, options: Option<plotting::PlotOptions>) -> Result<(), Error> {
+        std::fs::write(path, self.pdf(options)?)?;
+        Ok(())
+    }
+
+    /// the current page as svg image for notebooks.
+    fn _repr_svg_(&self) -> Result<String, Error> {
+        let options = plotting::PlotOptions::notebook("svg", Some(vec![self.page]))?;
+        let pages = self.plot_pages(&options)?;
+        Ok(pages
+            .into_iter()
+            .map(|(_, buffer)| String::from_utf8_lossy(&buffer).to_string())
+            .collect())
+    }
+
+    /// the current page as png image for notebooks.
+    fn _repr_png_(&self, py: Python) -> Result<PyObject, Error> {
+        let options = plotting::PlotOptions::notebook("png", Some(vec![self.page]))?;
+        let pages = self.plot_pages(&options)?;
+        let buffer: Vec<u8> = pages.into_iter().flat_map(|(_, buffer)| buffer).collect();
+        Ok(PyBytes::new(py, &buffer).into())
+    }
+
+    /// all pages with the page names for notebooks.
+    fn _repr_html_(&self) -> Result<String, Error> {
+        let options = plotting::PlotOptions::notebook("svg", None)?;
+        let pages = self.plot_pages(&options)?;
+        Ok(pages
+            .into_iter()
+            .map(|(name, buffer)| {
+                format!(
+                    "<figure>{}<figcaption>{}</figcaption></figure>",
+                    String::from_utf8_lossy(&buffer),
+                    name
+                )
+            })
+            .collect())
+    }
+
+    pub fn circuit(&mut self, pathlist: Vec<String>) -> Result<Circuit, Error> {
+        let netlist = self.netlist()?;
         let mut circuit = Circuit::new(String::from("draw circuit"), pathlist);
-        netlist.circuit(&mut circuit.circuit).unwrap();
-        circuit
+        netlist.circuit(&mut circuit.circuit)?;
+        Ok(circuit)
     }
 }
 
 impl Draw {
+    /// add a named position on the current page.
+    ///
+    /// labels with the same name and the units of an element replace the
+    /// anchor, all other names must be unique.
+    fn add_anchor(&mut self, kind: AnchorKind, name: &str, pos: &Array1<f64>) -> Result<(), Error> {
+        self.check_anchor(kind, name)?;
+        self.anchors.insert(
+            name.to_string(),
+            Anchor { kind, page: self.page, pos: pos.clone() },
+        );
+        Ok(())
+    }
+
+    /// check that the name can be added as anchor of the kind.
+    fn check_anchor(&self, kind: AnchorKind, name: &str) -> Result<(), Error> {
+        if let Some(anchor) = self.anchors.get(name) {
+            if anchor.kind != kind || kind == AnchorKind::Dot {
+                return Err(Error::DuplicateAnchor(name.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// add the anchors of the loaded symbols and labels.
+    ///
+    /// the symbols are anchored at their position, the first unit is used.
+    fn load_anchors(&mut self) -> Result<(), Error> {
+        let mut anchors = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                let (kind, name, pos) = match item {
+                    SchemaElement::Symbol(symbol) => match annotate::reference(symbol) {
+                        Some(reference) => (AnchorKind::Element, reference.to_string(), &symbol.at),
+                        None => continue,
+                    },
+                    SchemaElement::Label(label) => {
+                        (AnchorKind::Label, label.text.to_string(), &label.at)
+                    }
+                    SchemaElement::GlobalLabel(label) => {
+                        (AnchorKind::Label, label.text.to_string(), &label.at)
+                    }
+                    SchemaElement::HierarchicalLabel(label) => {
+                        (AnchorKind::Label, label.text.to_string(), &label.at)
+                    }
+                    _ => continue,
+                };
+                anchors.push((name, Anchor { kind, page, pos: pos.clone() }));
+            }
+        }
+        for (name, anchor) in anchors {
+            self.anchors.entry(name).or_insert(anchor);
+        }
+        Ok(())
+    }
+
+    /// get the position of the dot, named dots are looked up in the anchors.
+    fn dot_pos(&self, dot: &model::Dot) -> Result<Array1<f64>, Error> {
+        if let Some(name) = &dot.name {
+            self.anchors
+                .get(name)
+                .map(|anchor| anchor.pos.clone())
+                .ok_or_else(|| Error::AnchorNotFound(name.to_string()))
+        } else {
+            Ok(arr1(&[dot.pos[0], dot.pos[1]]))
+        }
+    }
+
+    fn add_sheet(&mut self, sheet: model::Sheet) -> Result<(), Error> {
+        let pos = if let Some(pos) = &sheet.pos {
+            let pos = unit::pos_mm(pos, self.grid);
+            arr1(&[pos.0, pos.1])
+        } else {
+            self.last_pos.clone()
+        };
+        let inputs = sheet.pins.iter().filter(|p| p.1 != "output").count();
+        let outputs = sheet.pins.len() - inputs;
+        let size = if let Some(size) = &sheet.size {
+            let size = unit::pos_mm(size, self.grid);
+            arr1(&[size.0, size.1])
+        } else {
+            let width = (sheet.name.len() as f64 * 1.27 / SHEET_PIN_SPACING).ceil() * SHEET_PIN_SPACING;
+            let height = (inputs.max(outputs) as f64 + 1.0) * SHEET_PIN_SPACING;
+            arr1(&[width.max(4.0 * SHEET_PIN_SPACING), height.max(2.0 * SHEET_PIN_SPACING)])
+        };
+        let pos = self.snap_pos("sheet", pos);
+        let size = self.snap(size);
+        let mut new_sheet = Sheet::new(pos.clone(), size.clone(), uuid!());
+        new_sheet.property.push(Property::new(
+            String::from("Sheet name"),
+            sheet.name.to_string(),
+            0,
+            round!(arr1(&[pos[0], pos[1] - 0.7])),
+            0.0,
+            None,
+        ));
+        new_sheet.property.push(Property::new(
+            String::from("Sheet file"),
+            sheet.filename.to_string(),
+            1,
+            round!(arr1(&[pos[0], pos[1] + size[1] + 0.7])),
+            0.0,
+            None,
+        ));
+        // inputs on the left, outputs on the right side of the sheet.
+        let mut left = 0;
+        let mut right = 0;
+        for (name, direction) in &sheet.pins {
+            let (at, angle) = if direction == "output" {
+                right += 1;
+                (arr1(&[pos[0] + size[0], pos[1] + right as f64 * SHEET_PIN_SPACING]), 0.0)
+            } else {
+                left += 1;
+                (arr1(&[pos[0], pos[1] + left as f64 * SHEET_PIN_SPACING]), 180.0)
+            };
+            new_sheet.pin.push(SheetPin::new(
+                name.as_str(),
+                direction.as_str(),
+                self.snap(at),
+                angle,
+                uuid!(),
+            ));
+        }
+        self.schema.push(self.page, SchemaElement::Sheet(new_sheet))?;
+        Ok(())
+    }
+
+    /// add the pages of the child sheets on the page.
+    ///
+    /// the sheet files are loaded relative to `dir`, the pages of the
+    /// children follow their parent page.
+    fn load_sheets(&mut self, dir: &Path, page: usize) -> Result<(), Error> {
+        let mut sheets = Vec::new();
+        for item in self.schema.iter(page)? {
+            if let SchemaElement::Sheet(sheet) = item {
+                let property = |key: &str| {
+                    sheet
+                        .property
+                        .iter()
+                        .find(|p| p.key == key)
+                        .map(|p| p.value.to_string())
+                };
+                if let (Some(name), Some(file)) = (property("Sheet name"), property("Sheet file")) {
+                    sheets.push((name, file));
+                }
+            }
+        }
+        for (name, file) in sheets {
+            let child = Schema::load(dir.join(&file).to_string_lossy().as_ref())?;
+            if let Some(child_page) = child.pages.into_iter().next() {
+                self.schema.pages.push(child_page);
+                self.pages.push(PageInfo { name, file, parent: Some(page) });
+                self.load_sheets(dir, self.schema.pages.len() - 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// get the position of a sheet pin by sheet name and pin name.
+    fn sheet_pin_pos(&self, name: &str, pin: &str) -> Option<Array1<f64>> {
+        for page in 0..self.schema.pages.len() {
+            if let Ok(iter) = self.schema.iter(page) {
+                for item in iter {
+                    if let SchemaElement::Sheet(sheet) = item {
+                        if sheet.property.iter().any(|p| p.key == "Sheet name" && p.value == name) {
+                            if let Some(pin) = sheet.pin.iter().find(|p| p.name == pin) {
+                                return Some(pin.at.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
     fn add_dot(&mut self, dot: &mut model::Dot) -> Result<(), Error> {
         let pos = if let (Some(atref), Some(atpin)) = (&dot.atref, &dot.atpin) {
-            let pos = self.pin_pos(atref.to_string(), atpin.to_string());
+            let pos = self.pin_pos(atref.to_string(), atpin.to_string())?;
             dot.pos = vec![pos[0], pos[1]];
             pos
         } else {
             self.last_pos.clone()
         };
+        let pos = self.snap_pos("junction", pos);
+        if let Some(name) = &dot.name {
+            self.add_anchor(AnchorKind::Dot, name, &pos)?;
+        }
         self.schema.push(
-            0,
+            self.page,
             SchemaElement::Junction(Junction::new(
                 pos,
                 uuid!(),
@@ -182,14 +784,15 @@ impl Draw {
 
     fn add_nc(&mut self, dot: &mut model::Nc) -> Result<(), Error> {
         let pos = if let (Some(atref), Some(atpin)) = (&dot.atref, &dot.atpin) {
-            let pos = self.pin_pos(atref.to_string(), atpin.to_string());
+            let pos = self.pin_pos(atref.to_string(), atpin.to_string())?;
             dot.pos = vec![pos[0], pos[1]];
             pos
         } else {
             self.last_pos.clone()
         };
+        let pos = self.snap_pos("no connect", pos);
         self.schema.push(
-            0,
+            self.page,
             SchemaElement::NoConnect(NoConnect::new(
                 pos,
                 uuid!(),
@@ -197,114 +800,226 @@ impl Draw {
         )?;
         Ok(())
     }
-    fn add_label(&mut self, label: model::Label) -> Result<(), Error> {
+    /// get the position of a new label and add the label name to the anchors.
+    fn label_pos(&mut self, kind: &str, name: &str) -> Result<Array1<f64>, Error> {
         let pos = self.last_pos.clone();
-        let mut new_label = Label::new(
-            round!(arr1(&[pos[0], pos[1]])),
+        let pos = self.snap_pos(kind, pos);
+        self.add_anchor(AnchorKind::Label, name, &pos)?;
+        Ok(pos)
+    }
+    fn add_label(&mut self, label: model::Label) -> Result<(), Error> {
+        // check the syntax of bus labels.
+        model::expand_bus(label.name.as_str(), &self.bus_aliases)?;
+        let pos = self.label_pos("label", label.name.as_str())?;
+        let mut new_label = Label::new(pos, label.angle, label.name.as_str(), uuid!());
+        new_label.effects.justify.push(label_justify(label.angle));
+        self.schema.push(self.page, SchemaElement::Label(new_label))?;
+        Ok(())
+    }
+    fn add_global_label(&mut self, label: model::GlobalLabel) -> Result<(), Error> {
+        let pos = self.label_pos("global label", label.name.as_str())?;
+        let mut new_label = GlobalLabel::new(
+            pos,
             label.angle,
             label.name.as_str(),
+            label.shape.as_str(),
             uuid!(),
         );
-        if label.angle == 180.0 {
-            new_label.effects.justify.push("right".to_string());
+        new_label.effects.justify.push(label_justify(label.angle));
+        self.schema.push(self.page, SchemaElement::GlobalLabel(new_label))?;
+        Ok(())
+    }
+    fn add_hierarchical_label(&mut self, label: model::HierarchicalLabel) -> Result<(), Error> {
+        let pos = self.label_pos("hierarchical label", label.name.as_str())?;
+        let mut new_label = HierarchicalLabel::new(
+            pos,
+            label.angle,
+            label.name.as_str(),
+            label.shape.as_str(),
+            uuid!(),
+        );
+        new_label.effects.justify.push(label_justify(label.angle));
+        self.schema.push(self.page, SchemaElement::HierarchicalLabel(new_label))?;
+        Ok(())
+    }
+    fn add_text(&mut self, text: model::Text) -> Result<(), Error> {
+        let pos = if let Some(pos) = &text.pos {
+            let pos = unit::pos_mm(pos, self.grid);
+            arr1(&[pos.0, pos.1])
         } else {
-            new_label.effects.justify.push("left".to_string());
-        }
-        self.schema.push(0, SchemaElement::Label(new_label))?;
+            self.last_pos.clone()
+        };
+        let mut new_text = Text::new(round!(pos), text.angle, text.text.as_str(), uuid!());
+        new_text.effects.size = text.size;
+        new_text.effects.justify = text.justify;
+        self.schema.push(self.page, SchemaElement::Text(new_text))?;
+        Ok(())
+    }
+    fn add_polyline(&mut self, pts: Vec<(f64, f64)>, width: f64, linetype: String) -> Result<(), Error> {
+        let mut stroke = Stroke::new();
+        stroke.width = width;
+        stroke.linetype = linetype;
+        let pts = Array2::from_shape_fn((pts.len(), 2), |(i, j)| if j == 0 { pts[i].0 } else { pts[i].1 });
+        self.schema.push(
+            self.page,
+            SchemaElement::Polyline(Polyline::new(round!(pts), stroke, uuid!())),
+        )?;
         Ok(())
     }
     fn add_line(&mut self, line: model::Line) -> Result<(), Error> {
+        let pts = self.line_pos(line)?;
+        let pts = self.snap_pts("wire", pts);
+        self.schema.push(
+            self.page,
+            SchemaElement::Wire(Wire::new(pts.clone(), Stroke::new(), uuid!())),
+        )?;
+        self.last_pos = arr1(&[pts[[1, 0]], pts[[1, 1]]]);
+        Ok(())
+    }
+    fn add_bus(&mut self, line: model::Line) -> Result<(), Error> {
+        let pts = self.line_pos(line)?;
+        let pts = self.snap_pts("bus", pts);
+        self.schema.push(
+            self.page,
+            SchemaElement::Bus(Bus::new(pts.clone(), Stroke::new(), uuid!())),
+        )?;
+        self.last_pos = arr1(&[pts[[1, 0]], pts[[1, 1]]]);
+        Ok(())
+    }
+    fn add_bus_entry(&mut self, entry: &mut model::BusEntry) -> Result<(), Error> {
+        let pos = if let Some(atdot) = &entry.atdot {
+            self.dot_pos(atdot)?
+        } else if let (Some(atref), Some(atpin)) = (&entry.atref, &entry.atpin) {
+            self.pin_pos(atref.to_string(), atpin.to_string())?
+        } else {
+            self.last_pos.clone()
+        };
+        let pos = self.snap_pos("bus entry", pos);
+        entry.pos = vec![pos[0], pos[1]];
+        self.schema.push(
+            self.page,
+            SchemaElement::BusEntry(BusEntry::new(
+                pos.clone(),
+                arr1(&[entry.size.0, entry.size.1]),
+                Stroke::new(),
+                uuid!(),
+            )),
+        )?;
+        // continue drawing at the wire end of the entry.
+        self.last_pos = self.snap(arr1(&[pos[0] + entry.size.0, pos[1] + entry.size.1]));
+        Ok(())
+    }
+    /// get the start and end position of a line.
+    fn line_pos(&self, line: model::Line) -> Result<Array2<f64>, Error> {
         let start_pos = if let Some(atdot) = line.atdot {
-            arr1(&[atdot.pos[0], atdot.pos[1]])
+            self.dot_pos(&atdot)?
         } else if let (Some(atpin), Some(atref)) = (line.atpin, line.atref) {
-            self.pin_pos(atref, atpin)
+            self.pin_pos(atref, atpin)?
         } else {
             self.last_pos.clone()
         };
         let end_pos = if let Some(end) = line.tox {
-            arr1(&[end[0], start_pos[1]])
+            arr1(&[self.dot_pos(&end)?[0], start_pos[1]])
         } else if let Some(end) = line.toy {
-            arr1(&[start_pos[0], end[1]])
+            arr1(&[start_pos[0], self.dot_pos(&end)?[1]])
         } else if let (Some(toref), Some(topin)) = (line.toxref, line.toxpin) {
-            arr1(&[self.pin_pos(toref, topin)[0], start_pos[1]])
+            arr1(&[self.pin_pos(toref, topin)?[0], start_pos[1]])
         } else if let (Some(toref), Some(topin)) = (line.toyref, line.toypin) {
-            arr1(&[start_pos[0], self.pin_pos(toref, topin)[1]])
+            arr1(&[start_pos[0], self.pin_pos(toref, topin)?[1]])
         } else {
-            match line.direction {
-                model::Direction::Up => arr1(&[start_pos[0], start_pos[1] - line.length]),
-                model::Direction::Down => arr1(&[start_pos[0], start_pos[1] + line.length]),
-                model::Direction::Left => arr1(&[start_pos[0] - line.length, start_pos[1]]),
-                model::Direction::Right => arr1(&[start_pos[0] + line.length, start_pos[1]]),
+            let length = line.length.mm(self.grid);
+            match line.direction.unwrap_or_else(|| self.direction.clone()) {
+                model::Direction::Up => arr1(&[start_pos[0], start_pos[1] - length]),
+                model::Direction::Down => arr1(&[start_pos[0], start_pos[1] + length]),
+                model::Direction::Left => arr1(&[start_pos[0] - length, start_pos[1]]),
+                model::Direction::Right => arr1(&[start_pos[0] + length, start_pos[1]]),
             }
         };
-        self.schema.push(
-            0,
-            SchemaElement::Wire(Wire::new(
-                round!(arr2(&[
-                    [start_pos[0], start_pos[1]],
-                    [end_pos[0], end_pos[1]]
-                ])),
-                Stroke::new(),
-                uuid!(),
-            )),
-        )?;
-        self.last_pos = end_pos;
-        Ok(())
+        Ok(arr2(&[
+            [start_pos[0], start_pos[1]],
+            [end_pos[0], end_pos[1]]
+        ]))
     }
-    fn add_symbol(&mut self, element: model::Element) -> Result<(), Error> {
+    fn add_symbol(&mut self, mut element: model::Element) -> Result<String, Error> {
         let lib_symbol = self.get_library(element.library.as_str())?;
-        let sym_pin = lib_symbol.get_pin(element.pin)?;
+        element.reference = self.annotate_reference(
+            element.reference.as_str(),
+            lib_symbol.power,
+            element.library.as_str(),
+            element.unit,
+        );
+        let sym_pin = lib_symbol.get_pin(element.pin).map_err(|_| {
+            Error::PinNotFound(element.reference.to_string(), element.pin.to_string())
+        })?;
 
         let pos = if let (Some(atref), Some(atpin)) = (element.atref, element.atpin) {
-            self.pin_pos(atref, atpin)
-        } else if let Some(dot) = element.atdot {
-            arr1(&[dot.pos[0], dot.pos[1]])
-        } else if let Some(pos) = element.pos {
+            self.pin_pos(atref, atpin)?
+        } else if let Some(dot) = &element.atdot {
+            self.dot_pos(dot)?
+        } else if let Some(pos) = &element.pos {
+            let pos = unit::pos_mm(pos, self.grid);
             arr1(&[pos.0, pos.1])
         } else {
             self.last_pos.clone()
         };
+        let pos = self.snap_pos(element.reference.as_str(), pos);
+        self.check_anchor(AnchorKind::Element, element.reference.as_str())?;
         // transform pin pos
-        let theta = -element.angle.to_radians();
-        let rot = arr2(&[[theta.cos(), -theta.sin()], [theta.sin(), theta.cos()]]);
-        let mut verts: Array1<f64> = sym_pin.at.dot(&rot);
-        verts = if let Some(mirror) = &element.mirror {
-            verts.dot(MIRROR.get(mirror).unwrap())
+        let matrix = orientation::matrix(element.angle, element.mirror.as_deref())?;
+        let transform = |at: &Array1<f64>| -> Array1<f64> { at.dot(&matrix) };
+        let mut verts: Array1<f64> = round!(arr1(&[pos[0], pos[1]]) - transform(&sym_pin.at));
+
+        // stretch the element to the target with wires on both sides.
+        let stretch = if let Some(end) = &element.tox {
+            Some((0, self.dot_pos(end)?))
+        } else if let Some(end) = &element.toy {
+            Some((1, self.dot_pos(end)?))
+        } else if let Some((toref, topin)) = &element.toxref {
+            Some((0, self.pin_pos(toref.to_string(), topin.to_string())?))
+        } else if let Some((toref, topin)) = &element.toyref {
+            Some((1, self.pin_pos(toref.to_string(), topin.to_string())?))
         } else {
-            verts.dot(MIRROR.get(&String::new()).unwrap())
+            None
         };
-        verts = arr1(&[pos[0], pos[1]]) - &verts;
-        verts = verts.mapv_into(|v| format!("{:.2}", v).parse::<f64>().unwrap());
-
-        if let Some(end_pos) = &element.endpos {
+        let mut wires = Vec::new();
+        let mut next_pos = None;
+        if let Some((axis, end_pos)) = stretch {
+            let end_pos = self.snap(end_pos);
             let pins = lib_symbol.pins(element.unit)?;
-            if pins.len() == 2 {
-                let mut verts2: Array1<f64> = pins.get(element.pin as usize).unwrap().at.dot(&rot);
-                verts2 = verts2.mapv_into(|v| format!("{:.2}", v).parse::<f64>().unwrap());
-                //TODO verts = verts.dot(sexp::MIRROR.get(mirror.as_str()).unwrap());
-                verts2 = arr1(&[pos[0], pos[1]]) - &verts2;
-                let sym_len = verts[0] - verts2[0];
-                let wire_len = ((end_pos[0] - pos[0]) - sym_len) / 2.0;
-                verts = verts + arr1(&[wire_len, 0.0]);
-                let mut wire1 = arr2(&[[pos[0], pos[1]], [pos[0] + wire_len, pos[1]]]);
-                wire1 = wire1.mapv_into(|v| format!("{:.2}", v).parse::<f64>().unwrap());
-                let mut wire2 = arr2(&[
-                    [pos[0] + wire_len + sym_len, pos[1]],
-                    [pos[0] + 2.0 * wire_len + sym_len, pos[1]],
-                ]);
-                wire2 = wire2.mapv_into(|v| format!("{:.2}", v).parse::<f64>().unwrap());
-                self.schema.push(
-                    0,
-                    SchemaElement::Wire(Wire::new(wire1, Stroke::new(), uuid!())),
-                )?;
-                self.schema.push(
-                    0,
-                    SchemaElement::Wire(Wire::new(wire2, Stroke::new(), uuid!())),
-                )?;
-                self.last_pos = arr1(&[pos[0] + 2.0 * wire_len + sym_len, pos[1]]);
+            if pins.len() != 2 {
+                return Err(Error::TwoPinsExpected(element.reference));
+            }
+            let other = if pins[0].number.0 == sym_pin.number.0 { pins[1] } else { pins[0] };
+            // offset from the anchor pin to the other pin.
+            let offset = round!(transform(&other.at) - transform(&sym_pin.at));
+            if offset[1 - axis].abs() > 0.01 {
+                return Err(Error::NotAligned(element.reference));
+            }
+            let distance = end_pos[axis] - pos[axis];
+            // the element starts with the other pin when the anchor pin faces the target.
+            let (first, sym_len) = if offset[axis] * distance < 0.0 {
+                (other, -offset[axis])
             } else {
-                panic!("tox and toy can only be used on symbols with two pins.")
+                (sym_pin, offset[axis])
+            };
+            if distance.abs() < sym_len.abs() || distance == 0.0 {
+                return Err(Error::StretchTooShort(element.reference));
             }
+            // the wires are snapped, the element is centered as close as the grid allows.
+            let wire_len = (((distance - sym_len) / 2.0) / self.grid).round() * self.grid;
+            verts = round!(arr1(&[pos[0], pos[1]]) - transform(&first.at));
+            verts[axis] += wire_len;
+            let point = |distance: f64| -> [f64; 2] {
+                let mut point = [pos[0], pos[1]];
+                point[axis] += distance;
+                point
+            };
+            for (start, end) in [(0.0, wire_len), (wire_len + sym_len, distance)] {
+                if (end - start).abs() > 0.001 {
+                    wires.push(round!(arr2(&[point(start), point(end)])));
+                }
+            }
+            next_pos = Some(arr1(&point(distance)));
         }
 
         let mut symbol = Symbol::from_library(
@@ -324,9 +1039,12 @@ impl Draw {
             } else {
                 true
             };
+            if let Some(in_bom) = properties.get("in_bom") {
+                symbol.in_bom = in_bom == "yes";
+            }
             // add the extra properties
             for (k, v) in properties.into_iter() {
-                if k != "on_schema" {
+                if k != "on_schema" && k != "in_bom" {
                     symbol.property.push(Property::new(
                         k,
                         v,
@@ -338,46 +1056,100 @@ impl Draw {
                 }
             }
         }
-        self.place_property(&mut symbol).unwrap();
-        self.schema.push(0, SchemaElement::Symbol(symbol))?;
-        Ok(())
+        let side = element.fields.as_deref().map(placement::Side::from).transpose()?;
+        self.place_property(&mut symbol, side)?;
+        self.check_pins(&symbol)?;
+        for wire in wires {
+            self.schema.push(
+                self.page,
+                SchemaElement::Wire(Wire::new(wire, Stroke::new(), uuid!())),
+            )?;
+        }
+        self.schema.push(self.page, SchemaElement::Symbol(symbol))?;
+        self.add_anchor(AnchorKind::Element, element.reference.as_str(), &pos)?;
+        if let Some(pos) = next_pos {
+            self.last_pos = pos;
+        }
+        Ok(element.reference)
     }
 
-    fn pin_pos(&self, reference: String, number: String) -> Array1<f64> {
-        let symbol = self.schema.get_symbol(reference.as_str(), 1).unwrap();
-        let library = self.schema.get_library(symbol.lib_id.as_str()).unwrap();
+    /// get the position of the symbol pin or of the sheet pin.
+    ///
+    /// symbols are looked up first, a sheet can not hide a symbol with
+    /// the same name.
+    fn pin_pos(&self, reference: String, number: String) -> Result<Array1<f64>, Error> {
+        let symbol = if let Some(symbol) = self.first_symbol(reference.as_str()) {
+            symbol
+        } else if let Some(pos) = self.sheet_pin_pos(reference.as_str(), number.as_str()) {
+            return Ok(pos);
+        } else {
+            return Err(Error::SymbolNotFound(reference));
+        };
+        let library = self
+            .schema
+            .get_library(symbol.lib_id.as_str())
+            .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
         for subsymbol in &library.symbols {
             for pin in &subsymbol.pin {
                 if pin.number.0 == number {
                     //TODO: Type
-                    let real_symbol = self
-                        .get_symbol(reference.as_str(), subsymbol.unit as u32)
-                        .unwrap();
-                    return Shape::transform(real_symbol, &pin.at);
+                    let real_symbol = if subsymbol.unit == 0 {
+                        symbol
+                    } else {
+                        self.get_symbol(reference.as_str(), subsymbol.unit as u32)
+                            .ok_or_else(|| Error::SymbolNotFound(reference.to_string()))?
+                    };
+                    return orientation::symbol_transform(real_symbol, &pin.at);
                 }
             }
         }
-        panic!("pin not found {}:{}", reference, number); //TODO return error
+        Err(Error::PinNotFound(reference, number))
     }
     /// return a library symbol when it exists or load it from the libraries.
     fn get_library(&mut self, name: &str) -> Result<LibrarySymbol, Error> {
         if let Some(lib) = self.schema.get_library(name) {
-            Ok(lib.clone())
+            let lib = lib.clone();
+            // every page needs its own copy of the library symbols.
+            let page = self
+                .schema
+                .page(self.page)
+                .ok_or(Error::PageNotFound(self.page))?;
+            if !page.libraries.iter().any(|l| l.lib_id == name) {
+                page.libraries.push(lib.clone());
+            }
+            Ok(lib)
         } else {
-            let mut lib = self.libs.get(name).unwrap();
+            let mut lib = self
+                .libs
+                .get(name)
+                .map_err(|_| Error::LibraryNotFound(name.to_string()))?;
             if !lib.extends.is_empty() {
-                let library = &name[0..name.find(':').unwrap()];
-                let mut extend_symbol = self.libs.get(format!("{}:{}", library, lib.extends.as_str()).as_str())?;
+                let (library, _) = name
+                    .split_once(':')
+                    .ok_or_else(|| Error::LibraryNotFound(name.to_string()))?;
+                let extends = format!("{}:{}", library, lib.extends);
+                let mut extend_symbol = self
+                    .libs
+                    .get(extends.as_str())
+                    .map_err(|_| Error::LibraryNotFound(extends.to_string()))?;
                 extend_symbol.property = lib.property.clone();
                 for subsymbol in &mut extend_symbol.symbols {
-                    let number = &subsymbol.lib_id[subsymbol.lib_id.find('_').unwrap()..subsymbol.lib_id.len()];
+                    let number = subsymbol
+                        .lib_id
+                        .find('_')
+                        .map(|index| subsymbol.lib_id[index..].to_string())
+                        .ok_or_else(|| Error::LibraryNotFound(subsymbol.lib_id.to_string()))?;
                     subsymbol.lib_id = format!("{}{}", lib.lib_id, number);
                 }
                 extend_symbol.lib_id = name.to_string();
                 lib = extend_symbol;
             }
             lib.lib_id = name.to_string();
-            self.schema.page(0).unwrap().libraries.push(lib.clone());
+            self.schema
+                .page(self.page)
+                .ok_or(Error::PageNotFound(self.page))?
+                .libraries
+                .push(lib.clone());
             Ok(lib)
         }
     }
@@ -387,200 +1159,22 @@ impl Draw {
         self.schema.get_symbol(reference, unit)
     }
 
-    fn place_property(&mut self, symbol: &mut Symbol) -> Result<(), Error> {
-        let vis_field = symbol
-            .property
-            .iter()
-            .filter_map(|node| {
-                if let Some(effects) = &node.effects {
-                    if !effects.hide {
-                        Option::from(node)
-                    } else {
-                        None
+    /// get the first symbol with the reference, regardless of the unit.
+    ///
+    /// symbols drawn in KiCad do not necessarily start with unit 1.
+    fn first_symbol(&self, reference: &str) -> Option<&Symbol> {
+        for page in 0..self.schema.pages.len() {
+            if let Ok(iter) = self.schema.iter(page) {
+                for item in iter {
+                    if let SchemaElement::Symbol(symbol) = item {
+                        if annotate::reference(symbol) == Some(reference) {
+                            return Some(symbol);
+                        }
                     }
-                } else {
-                    Option::from(node)
                 }
-            })
-            .count();
-        let lib = self.get_library(&symbol.lib_id).unwrap();
-
-        //get and sort the shape size
-        let _size = Shape::transform(symbol, &symbol.bounds(&lib).unwrap());
-        let _size = if _size[[0, 0]] > _size[[1, 0]] {
-            arr2(&[
-                [_size[[1, 0]], _size[[0, 1]]],
-                [_size[[0, 0]], _size[[1, 1]]],
-            ])
-        } else {
-            _size
-        };
-        let _size = if _size[[0, 1]] > _size[[1, 1]] {
-            arr2(&[
-                [_size[[0, 0]], _size[[1, 1]]],
-                [_size[[1, 0]], _size[[0, 1]]],
-            ])
-        } else {
-            _size
-        };
-        let positions = self.pin_position(symbol, &lib);
-        let mut offset = 0.0;
-        let pins = lib.pins(symbol.unit)?.len();
-        if pins == 1 {
-            if positions[0] == 1 {
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                            effects.justify.push("left".to_string());
-                        }
-                        p.at = arr1(&[_size[[1, 0]] - LABEL_BORDER, symbol.at[1]]);
-                        p.angle = 0.0 - symbol.angle;
-                    });
-                return Ok(());
-            } else if positions[3] == 1 {
-                //south
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                        }
-                        p.at = arr1(&[symbol.at[0], _size[[1, 1]] + LABEL_BORDER]);
-                        p.angle = 0.0 - symbol.angle;
-                    });
-                return Ok(());
-            } else if positions[2] == 1 {
-                //east
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                            effects.justify.push("right".to_string());
-                        }
-                        p.at = arr1(&[_size[[0, 0]] - LABEL_BORDER, symbol.at[1]]);
-                        p.angle = 0.0 - symbol.angle;
-                    });
-                return Ok(());
-            } else if positions[1] == 1 {
-                //south
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                        }
-                        p.at = arr1(&[symbol.at[0], _size[[0, 1]] - LABEL_BORDER]);
-                        p.angle = 0.0 - symbol.angle;
-                    });
-                return Ok(());
-            }
-        } else {
-            let top_pos = if _size[[0, 1]] < _size[[1, 1]] {
-                _size[[0, 1]] - ((vis_field as f64 - 1.0) * LABEL_BORDER) - LABEL_BORDER
-            } else {
-                _size[[1, 1]] - ((vis_field as f64 - 1.0) * LABEL_BORDER) - LABEL_BORDER
-            };
-            let bottom_pos = if _size[[0, 1]] < _size[[1, 1]] {
-                _size[[1, 1]] + LABEL_BORDER
-            } else {
-                _size[[0, 1]] + LABEL_BORDER
-            };
-            if positions[3] == 0 {
-                //north
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                        }
-                        p.at = arr1(&[symbol.at[0], top_pos - offset]);
-                        p.angle = 0.0 - symbol.angle;
-                        offset -= LABEL_BORDER;
-                    });
-                return Ok(());
-            } else if positions[2] == 0 {
-                //east
-                let top_pos = _size[[0, 1]] + ((_size[[1, 1]] - _size[[0, 1]]) / 2.0)
-                    - ((vis_field as f64 - 1.0) * LABEL_BORDER) / 2.0;
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                            effects.justify.push(String::from("left"));
-                        } else {
-                            let mut effects = Effects::new();
-                            effects.justify.push(String::from("left"));
-                            p.effects = Some(effects);
-                        }
-                        p.at = arr1(&[_size[[1, 0]] + LABEL_BORDER / 2.0, top_pos - offset]);
-                        p.angle = 360.0 - symbol.angle;
-                        offset -= LABEL_BORDER;
-                    });
-                return Ok(());
-            } else if positions[0] == 0 {
-                //west
-                let top_pos = _size[[0, 1]] + ((_size[[1, 1]] - _size[[0, 1]]) / 2.0)
-                    - ((vis_field as f64 - 1.0) * LABEL_BORDER) / 2.0;
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                            effects.justify.push(String::from("right"));
-                        } else {
-                            let mut effects = Effects::new();
-                            effects.justify.push(String::from("right"));
-                            p.effects = Some(effects);
-                        }
-                        p.at = arr1(&[_size[[1, 0]] - LABEL_BORDER / 2.0, top_pos - offset]);
-                        p.angle = 360.0 - symbol.angle;
-                        offset -= LABEL_BORDER;
-                    });
-                return Ok(());
-            } else if positions[1] == 0 {
-                //south
-                symbol
-                    .property
-                    .iter_mut()
-                    .filter(filter_properties)
-                    .sorted_by(sort_properties)
-                    .for_each(|p| {
-                        if let Some(effects) = &mut p.effects {
-                            effects.justify.clear();
-                        }
-                        p.at = arr1(&[symbol.at[0], bottom_pos + offset]);
-                        p.angle = 0.0 - symbol.angle;
-                        offset += LABEL_BORDER;
-                    });
-                return Ok(());
             }
         }
-        Err(Error::ParseError)
+        None
     }
 
     /// get the pin position
@@ -588,35 +1182,51 @@ impl Draw {
     ///   3
     /// 2   0
     ///   1
-    fn pin_position(&self, symbol: &Symbol, lib: &LibrarySymbol) -> Vec<usize> {
+    fn pin_position(&self, symbol: &Symbol, lib: &LibrarySymbol) -> Result<Vec<usize>, Error> {
         let mut position: Vec<usize> = vec![0; 4];
-        let symbol_shift: usize = (symbol.angle / 90.0).round() as usize;
-
-        for pin in lib.pins(symbol.unit).unwrap() {
-            let lib_pos: usize = (pin.angle / 90.0).round() as usize;
-            position[lib_pos] += 1;
-        }
-        position.rotate_right(symbol_shift);
-        if let Some(mirror) = &symbol.mirror {
-            if mirror == "x" {
-                position = vec![position[0], position[3], position[2], position[1]];
-            } else if mirror == "y" {
-                position = vec![position[2], position[1], position[0], position[3]];
-            }
+        for pin in lib.pins(symbol.unit)? {
+            position[orientation::pin_side(pin.angle, symbol.angle, symbol.mirror.as_deref())?] += 1;
         }
-        position
+        Ok(position)
+    }
+}
+
+/// labels pointing to the left are right justified.
+fn label_justify(angle: f64) -> String {
+    if angle == 180.0 {
+        String::from("right")
+    } else {
+        String::from("left")
     }
 }
 
 /// A Python module implemented in Rust.
 #[pymodule]
-fn elektron(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+fn elektron(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    error::register(py, m)?;
     m.add_class::<Draw>()?;
     m.add_class::<model::Line>()?;
     m.add_class::<model::Dot>()?;
     m.add_class::<model::Label>()?;
+    m.add_class::<model::GlobalLabel>()?;
+    m.add_class::<model::HierarchicalLabel>()?;
     m.add_class::<model::Element>()?;
     m.add_class::<model::Nc>()?;
+    m.add_class::<model::Bus>()?;
+    m.add_class::<model::BusEntry>()?;
+    m.add_function(wrap_pyfunction!(model::bus_members, m)?)?;
+    m.add_function(wrap_pyfunction!(unit::length, m)?)?;
+    m.add_class::<model::Sheet>()?;
+    m.add_class::<model::Text>()?;
+    m.add_class::<model::Polyline>()?;
+    m.add_class::<model::Rectangle>()?;
+    m.add_class::<SheetContext>()?;
+    m.add_class::<BranchContext>()?;
+    m.add_class::<erc::Violation>()?;
+    m.add_class::<query::PlacedSymbol>()?;
+    m.add_class::<query::PlacedPin>()?;
+    m.add_class::<plotting::PlotOptions>()?;
+    m.add_class::<theme::Theme>()?;
     m.add_class::<circuit::Circuit>()?;
     m.add_class::<circuit::Simulation>()?;
     Ok(())
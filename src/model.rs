@@ -1,10 +1,13 @@
 #![allow(clippy::borrow_deref_ref)]
 use std::collections::HashMap;
 
-use ndarray::Array1;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
+use crate::error::Error;
+use crate::placement::Side;
+use crate::unit::{extract_length, extract_pos, position, Length, Pos};
+
 #[derive(Debug, Clone)]
 pub enum Direction {
     Up,
@@ -13,11 +16,33 @@ pub enum Direction {
     Right,
 }
 
-#[pyclass]
+impl Direction {
+    pub fn from(name: &str) -> Result<Self, Error> {
+        match name {
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Err(Error::InvalidValue(String::from("direction"), name.to_string())),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+#[pyclass(subclass)]
 #[derive(Debug, Clone)]
 pub struct Line {
-    pub direction: Direction,
-    pub length: f64,
+    /// the direction, the current direction of the drawing when not set.
+    pub direction: Option<Direction>,
+    pub length: Length,
     pub atref: Option<String>,
     pub atpin: Option<String>,
     pub atdot: Option<Dot>,
@@ -25,8 +50,8 @@ pub struct Line {
     pub toxpin: Option<String>,
     pub toyref: Option<String>,
     pub toypin: Option<String>,
-    pub tox: Option<Array1<f64>>,
-    pub toy: Option<Array1<f64>>,
+    pub tox: Option<Dot>,
+    pub toy: Option<Dot>,
 }
 #[pymethods]
 impl Line {
@@ -35,8 +60,8 @@ impl Line {
         //(Self, DrawBase) {
         // (Line { direction: String::from("left"), length: 2.54 }, DrawBase::new())
         Line {
-            direction: Direction::Right,
-            length: 2.54,
+            direction: None,
+            length: Length::Mm(2.54),
             atref: None,
             atpin: None,
             atdot: None,
@@ -49,111 +74,266 @@ impl Line {
         }
     }
     pub fn up<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
-        slf.direction = Direction::Up;
+        slf.direction = Some(Direction::Up);
         slf
     }
     pub fn down<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
-        slf.direction = Direction::Down;
+        slf.direction = Some(Direction::Down);
         slf
     }
     pub fn left<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
-        slf.direction = Direction::Left;
+        slf.direction = Some(Direction::Left);
         slf
     }
     pub fn right<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
-        slf.direction = Direction::Right;
+        slf.direction = Some(Direction::Right);
         slf
     }
-    pub fn length<'py>(mut slf: PyRefMut<'py, Self>, _py: Python, len: f64) -> PyRefMut<'py, Self> {
-        slf.length = len;
-        slf
+    /// the length in mm or as string with a unit, like `200mil`.
+    pub fn length<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        len: &'_ PyAny,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.length = extract_length(len)?;
+        Ok(slf)
     }
     pub fn at<'py>(
         mut slf: PyRefMut<'py, Self>,
         _py: Python,
         reference: &'_ PyAny,
         pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = reference.extract();
-        if let Ok(dot) = dot {
-            slf.atdot = Some(dot);
-            return slf;
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.set_at(reference, pin)?;
+        Ok(slf)
+    }
+    pub fn tox<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        element: &'_ PyAny,
+        pin: Option<&'_ PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.set_tox(element, pin)?;
+        Ok(slf)
+    }
+    pub fn toy<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        element: &'_ PyAny,
+        pin: Option<&'_ PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.set_toy(element, pin)?;
+        Ok(slf)
+    }
+}
+
+impl Line {
+    fn set_at(&mut self, reference: &'_ PyAny, pin: Option<&'_ PyAny>) -> PyResult<()> {
+        if let Some(dot) = anchor(reference, pin) {
+            self.atdot = Some(dot);
+            return Ok(());
         }
         if let Some(pin) = pin {
             let reference: Result<String, PyErr> = reference.extract();
             let pin: Result<String, PyErr> = pin.extract();
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
-                slf.atref = Some(reference.to_string());
-                slf.atpin = Some(pin);
-                return slf;
+                self.atref = Some(reference.to_string());
+                self.atpin = Some(pin);
+                return Ok(());
             }
         }
-        panic!("unknown type for at: {:?}", reference);
+        Err(Error::UnknownType(String::from("at"), reference.to_string()).into())
     }
-    pub fn tox<'py>(
-        mut slf: PyRefMut<'py, Self>,
-        _py: Python,
-        element: &'_ PyAny,
-        pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = element.extract();
-        if let Ok(dot) = dot {
-            slf.tox = Some(Array1::from_vec(dot.pos));
-            return slf;
+    fn set_tox(&mut self, element: &'_ PyAny, pin: Option<&'_ PyAny>) -> PyResult<()> {
+        if let Some(dot) = anchor(element, pin) {
+            self.tox = Some(dot);
+            return Ok(());
         }
         if let Some(pin) = pin {
             let reference: Result<String, PyErr> = element.extract();
             let pin: Result<String, PyErr> = pin.extract();
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
-                slf.toxref = Some(reference.to_string());
-                slf.toxpin = Some(pin);
-                return slf;
+                self.toxref = Some(reference.to_string());
+                self.toxpin = Some(pin);
+                return Ok(());
             }
         }
-        panic!("unknown type for at: {:?}", element);
+        Err(Error::UnknownType(String::from("tox"), element.to_string()).into())
     }
-    pub fn toy<'py>(
+    fn set_toy(&mut self, element: &'_ PyAny, pin: Option<&'_ PyAny>) -> PyResult<()> {
+        if let Some(dot) = anchor(element, pin) {
+            self.toy = Some(dot);
+            return Ok(());
+        }
+        if let Some(pin) = pin {
+            let reference: Result<String, PyErr> = element.extract();
+            let pin: Result<String, PyErr> = pin.extract();
+            if let (Ok(reference), Ok(pin)) = (&reference, pin) {
+                self.toyref = Some(reference.to_string());
+                self.toypin = Some(pin);
+                return Ok(());
+            }
+        }
+        Err(Error::UnknownType(String::from("toy"), element.to_string()).into())
+    }
+}
+
+/// A bus, drawn with the same api as the `Line`.
+#[pyclass(extends = Line)]
+#[derive(Debug, Clone)]
+pub struct Bus {}
+#[pymethods]
+impl Bus {
+    #[new]
+    fn new() -> (Self, Line) {
+        (Bus {}, Line::new())
+    }
+}
+
+/// A bus entry, the size is the offset from the bus to the wire.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct BusEntry {
+    pub pos: Vec<f64>,
+    pub size: (f64, f64),
+    pub atdot: Option<Dot>,
+    pub atref: Option<String>,
+    pub atpin: Option<String>,
+}
+#[pymethods]
+impl BusEntry {
+    #[new]
+    fn new() -> Self {
+        BusEntry {
+            pos: vec![0.0, 0.0],
+            size: (2.54, 2.54),
+            atdot: None,
+            atref: None,
+            atpin: None,
+        }
+    }
+    pub fn up<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
+        slf.size.1 = -slf.size.1.abs();
+        slf
+    }
+    pub fn down<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
+        slf.size.1 = slf.size.1.abs();
+        slf
+    }
+    pub fn left<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
+        slf.size.0 = -slf.size.0.abs();
+        slf
+    }
+    pub fn right<'py>(mut slf: PyRefMut<'py, Self>, _py: Python) -> PyRefMut<'py, Self> {
+        slf.size.0 = slf.size.0.abs();
+        slf
+    }
+    pub fn at<'py>(
         mut slf: PyRefMut<'py, Self>,
         _py: Python,
-        element: &'_ PyAny,
+        reference: &'_ PyAny,
         pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = element.extract();
-        if let Ok(dot) = dot {
-            slf.toy = Some(Array1::from_vec(dot.pos));
-            return slf;
-        }
-        let label: Result<Label, PyErr> = element.extract();
-        if let Ok(label) = label {
-            slf.toy = Some(Array1::from_vec(label.pos));
-            return slf;
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        if let Some(dot) = anchor(reference, pin) {
+            slf.atdot = Some(dot);
+            return Ok(slf);
         }
         if let Some(pin) = pin {
-            let reference: Result<String, PyErr> = element.extract();
+            let reference: Result<String, PyErr> = reference.extract();
             let pin: Result<String, PyErr> = pin.extract();
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
-                slf.toyref = Some(reference.to_string());
-                slf.toypin = Some(pin);
-                return slf;
+                slf.atref = Some(reference.to_string());
+                slf.atpin = Some(pin);
+                return Ok(slf);
+            }
+        }
+        Err(Error::UnknownType(String::from("at"), reference.to_string()).into())
+    }
+}
+
+/// expand a bus label like `D[0..7]` or `{A B}` to the member nets.
+///
+/// names that are no bus are returned as the only member.
+#[pyfunction]
+pub fn bus_members(name: &str) -> Result<Vec<String>, Error> {
+    expand_bus(name, &HashMap::new())
+}
+
+/// expand the bus name, the aliases are replaced with their members.
+///
+/// a range `D[0..7]` expands to `D0` to `D7`, a group `{A B}` to its
+/// members and a named group `ADDR{A B}` to `ADDR.A` and `ADDR.B`. other
+/// names with brackets, like `D[3]`, are plain net names.
+pub fn expand_bus(name: &str, aliases: &HashMap<String, Vec<String>>) -> Result<Vec<String>, Error> {
+    if let Some(members) = aliases.get(name) {
+        let mut result = Vec::new();
+        for member in members {
+            result.extend(expand_bus(member, &HashMap::new())?);
+        }
+        return Ok(result);
+    }
+    if let Some(start) = name.find('{') {
+        if !name.ends_with('}') {
+            return Err(Error::InvalidValue(String::from("bus"), name.to_string()));
+        }
+        let prefix = &name[0..start];
+        let mut result = Vec::new();
+        for member in name[start + 1..name.len() - 1].split_whitespace() {
+            for member in expand_bus(member, aliases)? {
+                if prefix.is_empty() {
+                    result.push(member);
+                } else {
+                    result.push(format!("{}.{}", prefix, member));
+                }
             }
         }
-        panic!("unknown type for toy: {:?}:{:?}", element, pin);
+        return Ok(result);
     }
+    if let (Some(start), Some(end)) = (name.find('['), name.rfind(']')) {
+        if start < end && name[start + 1..end].contains("..") {
+            let prefix = &name[0..start];
+            let range: Vec<&str> = name[start + 1..end].split("..").collect();
+            if range.len() == 2 && end == name.len() - 1 {
+                if let (Ok(first), Ok(last)) =
+                    (range[0].trim().parse::<u32>(), range[1].trim().parse::<u32>())
+                {
+                    return if first <= last {
+                        Ok((first..=last).map(|i| format!("{}{}", prefix, i)).collect())
+                    } else {
+                        Ok((last..=first).rev().map(|i| format!("{}{}", prefix, i)).collect())
+                    };
+                }
+            }
+            return Err(Error::InvalidValue(String::from("bus"), name.to_string()));
+        }
+    }
+    Ok(vec![name.to_string()])
+}
+
+/// check if the name is a bus label.
+pub fn is_bus(name: &str, aliases: &HashMap<String, Vec<String>>) -> bool {
+    aliases.contains_key(name)
+        || expand_bus(name, aliases).map_or(false, |members| members != [name.to_string()])
 }
 
 #[pyclass]
 #[derive(Debug, Clone)]
 pub struct Dot {
+    #[pyo3(get)]
     pub pos: Vec<f64>,
+    /// the name of the anchor, named dots can be looked up in the drawing.
+    #[pyo3(get)]
+    pub name: Option<String>,
     pub atref: Option<String>,
     pub atpin: Option<String>,
 }
 #[pymethods]
 impl Dot {
     #[new]
-    fn new() -> Self {
+    pub fn new(name: Option<String>) -> Self {
         Dot {
             pos: vec![0.0, 0.0],
+            name,
             atref: None,
             atpin: None,
         }
@@ -163,17 +343,17 @@ impl Dot {
         _py: Python,
         reference: &'_ PyAny,
         pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
+    ) -> PyResult<PyRefMut<'py, Self>> {
         if let Some(pin) = pin {
             let reference: Result<String, PyErr> = reference.extract();
             let pin: Result<String, PyErr> = pin.extract();
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
                 slf.atref = Some(reference.to_string());
                 slf.atpin = Some(pin);
-                return slf;
+                return Ok(slf);
             }
         }
-        panic!("unknown type for at: {:?}", reference);
+        Err(Error::UnknownType(String::from("at"), reference.to_string()).into())
     }
 }
 
@@ -199,17 +379,17 @@ impl Nc {
         _py: Python,
         reference: &'_ PyAny,
         pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
+    ) -> PyResult<PyRefMut<'py, Self>> {
         if let Some(pin) = pin {
             let reference: Result<String, PyErr> = reference.extract();
             let pin: Result<String, PyErr> = pin.extract();
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
                 slf.atref = Some(reference.to_string());
                 slf.atpin = Some(pin);
-                return slf;
+                return Ok(slf);
             }
         }
-        panic!("unknown type for at: {:?}", reference);
+        Err(Error::UnknownType(String::from("at"), reference.to_string()).into())
     }
 }
 
@@ -236,9 +416,134 @@ impl Label {
     }
 }
 
+/// get the kicad name of the label shape.
+fn label_shape(shape: &str) -> Result<String, Error> {
+    match shape {
+        "input" | "output" | "bidirectional" | "passive" => Ok(shape.to_string()),
+        "tri_state" | "tri-state" | "tristate" => Ok(String::from("tri_state")),
+        _ => Err(Error::InvalidValue(String::from("shape"), shape.to_string())),
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct GlobalLabel {
+    pub pos: Vec<f64>,
+    pub name: String,
+    pub angle: f64,
+    pub shape: String,
+}
+#[pymethods]
+impl GlobalLabel {
+    #[new]
+    #[args(shape = "\"passive\"")]
+    pub fn new(name: String, shape: &str) -> PyResult<Self> {
+        Ok(GlobalLabel { pos: vec![0.0, 0.0], name, angle: 0.0, shape: label_shape(shape)? })
+    }
+    pub fn rotate<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        angle: f64,
+    ) -> PyRefMut<'py, Self> {
+        slf.angle = angle;
+        slf
+    }
+    pub fn shape<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        shape: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.shape = label_shape(shape)?;
+        Ok(slf)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct HierarchicalLabel {
+    pub pos: Vec<f64>,
+    pub name: String,
+    pub angle: f64,
+    pub shape: String,
+}
+#[pymethods]
+impl HierarchicalLabel {
+    #[new]
+    #[args(shape = "\"passive\"")]
+    pub fn new(name: String, shape: &str) -> PyResult<Self> {
+        Ok(HierarchicalLabel { pos: vec![0.0, 0.0], name, angle: 0.0, shape: label_shape(shape)? })
+    }
+    pub fn rotate<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        angle: f64,
+    ) -> PyRefMut<'py, Self> {
+        slf.angle = angle;
+        slf
+    }
+    pub fn shape<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        shape: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.shape = label_shape(shape)?;
+        Ok(slf)
+    }
+}
+
+/// get the anchor for a dot, label, element or anchor name.
+///
+/// names are resolved when the item is drawn, a string with a pin
+/// is a symbol pin and not an anchor.
+pub fn anchor(item: &'_ PyAny, pin: Option<&'_ PyAny>) -> Option<Dot> {
+    if let Ok(dot) = item.extract::<Dot>() {
+        return Some(dot);
+    }
+    let name = if let Ok(label) = item.extract::<Label>() {
+        label.name
+    } else if let Ok(label) = item.extract::<GlobalLabel>() {
+        label.name
+    } else if let Ok(label) = item.extract::<HierarchicalLabel>() {
+        label.name
+    } else if let Ok(element) = item.extract::<Element>() {
+        element.reference
+    } else if let (Ok(name), None) = (item.extract::<String>(), pin) {
+        name
+    } else {
+        return None;
+    };
+    Some(Dot::new(Some(name)))
+}
+
+/// get the target for stretching an element.
+///
+/// the target is either a `Dot`, a `Label` or a reference and pin.
+fn stretch_target(
+    name: &str,
+    element: &'_ PyAny,
+    pin: Option<&'_ PyAny>,
+) -> PyResult<(Option<Dot>, Option<(String, String)>)> {
+    if let Some(dot) = anchor(element, pin) {
+        return Ok((Some(dot), None));
+    }
+    let target: Result<(String, String), PyErr> = element.extract();
+    if let Ok(target) = target {
+        return Ok((None, Some(target)));
+    }
+    if let Some(pin) = pin {
+        let reference: Result<String, PyErr> = element.extract();
+        let pin: Result<String, PyErr> = pin.extract();
+        if let (Ok(reference), Ok(pin)) = (reference, pin) {
+            return Ok((None, Some((reference, pin))));
+        }
+    }
+    Err(Error::UnknownType(name.to_string(), element.to_string()).into())
+}
+
 #[pyclass]
 #[derive(Debug, Clone)]
 pub struct Element {
+    #[pyo3(get)]
     pub reference: String,
     pub library: String,
     pub value: String,
@@ -246,12 +551,16 @@ pub struct Element {
     pub args: Option<HashMap<String, String>>,
     pub angle: f64,
     pub pin: u32,
-    pub pos: Option<(f64, f64)>,
+    pub pos: Option<Pos>,
     pub atref: Option<String>,
     pub atpin: Option<String>,
     pub atdot: Option<Dot>,
-    pub endpos: Option<Array1<f64>>,
+    pub tox: Option<Dot>,
+    pub toy: Option<Dot>,
+    pub toxref: Option<(String, String)>,
+    pub toyref: Option<(String, String)>,
     pub mirror: Option<String>,
+    pub fields: Option<String>,
 }
 #[pymethods]
 impl Element {
@@ -285,8 +594,12 @@ impl Element {
             atref: None,
             atpin: None,
             atdot: None,
-            endpos: None,
+            tox: None,
+            toy: None,
+            toxref: None,
+            toyref: None,
             mirror: None,
+            fields: None,
         }
     }
     pub fn anchor<'py>(mut slf: PyRefMut<'py, Self>, _py: Python, pin: u32) -> PyRefMut<'py, Self> {
@@ -306,16 +619,14 @@ impl Element {
         _py: Python,
         reference: &'_ PyAny,
         pin: Option<&'_ PyAny>,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = reference.extract();
-        if let Ok(dot) = dot {
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        if let Some(dot) = anchor(reference, pin) {
             slf.atdot = Some(dot);
-            return slf;
+            return Ok(slf);
         }
-        let dot: Result<(f64, f64), PyErr> = reference.extract();
-        if let Ok(dot) = dot {
-            slf.pos = Some(dot);
-            return slf;
+        if let Some(pos) = extract_pos(reference) {
+            slf.pos = Some(pos);
+            return Ok(slf);
         }
         if let Some(pin) = pin {
             let reference: Result<String, PyErr> = reference.extract();
@@ -323,44 +634,32 @@ impl Element {
             if let (Ok(reference), Ok(pin)) = (&reference, pin) {
                 slf.atref = Some(reference.to_string());
                 slf.atpin = Some(pin);
-                return slf;
+                return Ok(slf);
             }
         }
-        panic!("unknown type for at: {:?}", reference);
+        Err(Error::UnknownType(String::from("at"), reference.to_string()).into())
     }
     pub fn tox<'py>(
         mut slf: PyRefMut<'py, Self>,
         _py: Python,
         element: &'_ PyAny,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = element.extract();
-        if let Ok(dot) = dot {
-            slf.endpos = Some(Array1::from_vec(dot.pos));
-            return slf;
-        }
-        let label: Result<Label, PyErr> = element.extract();
-        if let Ok(label) = label {
-            slf.endpos = Some(Array1::from_vec(label.pos));
-            return slf;
-        }
-        panic!("unknown type for tox: {:?}", element);
+        pin: Option<&'_ PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let (pos, target) = stretch_target("tox", element, pin)?;
+        slf.tox = pos;
+        slf.toxref = target;
+        Ok(slf)
     }
     pub fn toy<'py>(
         mut slf: PyRefMut<'py, Self>,
         _py: Python,
         element: &'_ PyAny,
-    ) -> PyRefMut<'py, Self> {
-        let dot: Result<Dot, PyErr> = element.extract();
-        if let Ok(dot) = dot {
-            slf.endpos = Some(Array1::from_vec(dot.pos));
-            return slf;
-        }
-        let label: Result<Label, PyErr> = element.extract();
-        if let Ok(label) = label {
-            slf.endpos = Some(Array1::from_vec(label.pos));
-            return slf;
-        }
-        panic!("unknown type for toy: {:?}", element);
+        pin: Option<&'_ PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let (pos, target) = stretch_target("toy", element, pin)?;
+        slf.toy = pos;
+        slf.toyref = target;
+        Ok(slf)
     }
     pub fn mirror<'py>(
         mut slf: PyRefMut<'py, Self>,
@@ -370,4 +669,239 @@ impl Element {
         slf.mirror = Some(mirror);
         slf
     }
+    /// place the fields on the side: north, east, west or south.
+    pub fn fields<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        side: String,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Side::from(side.as_str())?;
+        slf.fields = Some(side);
+        Ok(slf)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub filename: String,
+    pub pins: Vec<(String, String)>,
+    pub pos: Option<Pos>,
+    pub size: Option<Pos>,
+}
+#[pymethods]
+impl Sheet {
+    #[new]
+    pub fn new(name: String, filename: Option<String>) -> Self {
+        let filename = if let Some(filename) = filename {
+            filename
+        } else {
+            format!("{}.kicad_sch", name)
+        };
+        Sheet {
+            name,
+            filename,
+            pins: Vec::new(),
+            pos: None,
+            size: None,
+        }
+    }
+    pub fn pin<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        name: String,
+        direction: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.pins.push((name, label_shape(direction)?));
+        Ok(slf)
+    }
+    /// the position as tuple of lengths.
+    pub fn at<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        pos: &'_ PyAny,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.pos = Some(position("at", pos)?);
+        Ok(slf)
+    }
+    /// the width and height in mm or as string with a unit.
+    pub fn size<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        width: &'_ PyAny,
+        height: &'_ PyAny,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.size = Some((extract_length(width)?, extract_length(height)?));
+        Ok(slf)
+    }
+}
+
+/// get the kicad name of the stroke type.
+fn stroke_type(stroke: &str) -> Result<String, Error> {
+    match stroke {
+        "default" | "solid" | "dash" | "dot" | "dash_dot" | "dash_dot_dot" => Ok(stroke.to_string()),
+        _ => Err(Error::InvalidValue(String::from("stroke"), stroke.to_string())),
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Text {
+    pub text: String,
+    pub pos: Option<Pos>,
+    pub angle: f64,
+    pub size: f64,
+    pub justify: Vec<String>,
+}
+#[pymethods]
+impl Text {
+    #[new]
+    #[args(size = "1.27")]
+    pub fn new(text: String, size: f64) -> Self {
+        Text { text, pos: None, angle: 0.0, size, justify: Vec::new() }
+    }
+    /// the position as tuple of lengths.
+    pub fn at<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        pos: &'_ PyAny,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.pos = Some(position("at", pos)?);
+        Ok(slf)
+    }
+    pub fn rotate<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        angle: f64,
+    ) -> PyRefMut<'py, Self> {
+        slf.angle = angle;
+        slf
+    }
+    pub fn size<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        size: f64,
+    ) -> PyRefMut<'py, Self> {
+        slf.size = size;
+        slf
+    }
+    /// justify the text: left, right, top, bottom or mirror.
+    pub fn justify<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        justify: Vec<String>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        for j in &justify {
+            if !["left", "right", "top", "bottom", "mirror"].contains(&j.as_str()) {
+                return Err(Error::InvalidValue(String::from("justify"), j.to_string()).into());
+            }
+        }
+        slf.justify = justify;
+        Ok(slf)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    pub pts: Vec<Pos>,
+    pub width: f64,
+    pub stroke: String,
+}
+#[pymethods]
+impl Polyline {
+    /// the points as tuples of lengths.
+    #[new]
+    pub fn new(pts: Vec<&'_ PyAny>) -> PyResult<Self> {
+        let pts = pts.into_iter().map(|p| position("pts", p)).collect::<PyResult<Vec<Pos>>>()?;
+        Ok(Polyline { pts, width: 0.0, stroke: String::from("default") })
+    }
+    pub fn stroke<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        width: f64,
+        stroke: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.width = width;
+        slf.stroke = stroke_type(stroke)?;
+        Ok(slf)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Rectangle {
+    pub start: Pos,
+    pub end: Pos,
+    pub width: f64,
+    pub stroke: String,
+}
+#[pymethods]
+impl Rectangle {
+    /// the corners as tuples of lengths.
+    #[new]
+    pub fn new(start: &'_ PyAny, end: &'_ PyAny) -> PyResult<Self> {
+        Ok(Rectangle {
+            start: position("start", start)?,
+            end: position("end", end)?,
+            width: 0.0,
+            stroke: String::from("default"),
+        })
+    }
+    pub fn stroke<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        _py: Python,
+        width: f64,
+        stroke: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.width = width;
+        slf.stroke = stroke_type(stroke)?;
+        Ok(slf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{expand_bus, is_bus};
+
+    #[test]
+    fn expand_range() {
+        assert_eq!(
+            expand_bus("D[0..3]", &HashMap::new()).unwrap(),
+            vec!["D0", "D1", "D2", "D3"]
+        );
+        assert_eq!(expand_bus("D[2..1]", &HashMap::new()).unwrap(), vec!["D2", "D1"]);
+    }
+
+    #[test]
+    fn net_names_with_brackets() {
+        assert_eq!(expand_bus("D[3]", &HashMap::new()).unwrap(), vec!["D[3]"]);
+        assert_eq!(expand_bus("x[n]", &HashMap::new()).unwrap(), vec!["x[n]"]);
+        assert!(!is_bus("D[3]", &HashMap::new()));
+        assert!(expand_bus("D[a..b]", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn expand_group() {
+        assert_eq!(expand_bus("{SDA SCL}", &HashMap::new()).unwrap(), vec!["SDA", "SCL"]);
+        assert_eq!(
+            expand_bus("ADDR{A[0..1] CLK}", &HashMap::new()).unwrap(),
+            vec!["ADDR.A0", "ADDR.A1", "ADDR.CLK"]
+        );
+        assert!(expand_bus("{SDA SCL", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn expand_alias() {
+        let aliases = HashMap::from([(
+            String::from("I2C"),
+            vec![String::from("SDA"), String::from("SCL")],
+        )]);
+        assert!(is_bus("I2C", &aliases));
+        assert_eq!(expand_bus("I2C", &aliases).unwrap(), vec!["SDA", "SCL"]);
+        assert_eq!(expand_bus("BUS{I2C}", &aliases).unwrap(), vec!["BUS.SDA", "BUS.SCL"]);
+    }
 }
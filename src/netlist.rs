@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use elektron_sexp::{Label, SchemaElement, Sheet};
+use elektron_spice::Netlist;
+use ndarray::{arr1, Array1};
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::model::{expand_bus, is_bus};
+use crate::Draw;
+
+/// the distance between the pages in the paged netlist.
+const PAGE_OFFSET: f64 = 100_000.0;
+
+/// move the connected items of a page in x direction.
+fn shift(item: &mut SchemaElement, offset: f64) {
+    match item {
+        SchemaElement::Symbol(symbol) => symbol.at[0] += offset,
+        SchemaElement::Wire(wire) => wire.pts.column_mut(0).mapv_inplace(|x| x + offset),
+        SchemaElement::Bus(bus) => bus.pts.column_mut(0).mapv_inplace(|x| x + offset),
+        SchemaElement::BusEntry(entry) => entry.at[0] += offset,
+        SchemaElement::Junction(junction) => junction.at[0] += offset,
+        SchemaElement::NoConnect(nc) => nc.at[0] += offset,
+        SchemaElement::Label(label) => label.at[0] += offset,
+        _ => {}
+    }
+}
+
+/// get the net name at the position of the page from a paged netlist.
+pub(crate) fn node_name(netlist: &Netlist, page: usize, pos: &Array1<f64>) -> Option<String> {
+    netlist
+        .node_name(&arr1(&[pos[0] + page as f64 * PAGE_OFFSET, pos[1]]))
+        .map(|name| name.to_string())
+}
+
+/// get the name of the sheet.
+pub(crate) fn sheet_name(sheet: &Sheet) -> String {
+    sheet
+        .property
+        .iter()
+        .find(|p| p.key == "Sheet name")
+        .map(|p| p.value.to_string())
+        .unwrap_or_default()
+}
+
+impl Draw {
+    /// get the path of the page in the sheet hierarchy, `/` for the root page.
+    pub(crate) fn sheet_path(&self, page: usize) -> String {
+        match self.pages.get(page) {
+            Some(info) => match info.parent {
+                Some(parent) => format!("{}{}/", self.sheet_path(parent), info.name),
+                None => String::from("/"),
+            },
+            None => String::from("/"),
+        }
+    }
+
+    /// the prefix of the local net names on the page, the root page has none.
+    fn net_prefix(&self, page: usize) -> String {
+        if page == 0 {
+            String::new()
+        } else {
+            self.sheet_path(page)
+        }
+    }
+
+    /// get the net names of the bus members that enter the page through a sheet pin.
+    ///
+    /// the members keep the net name of the parent page.
+    fn bus_pin_members(&self, page: usize) -> Result<HashMap<String, String>, Error> {
+        let mut members = HashMap::new();
+        let info = self.pages.get(page).ok_or(Error::PageNotFound(page))?;
+        if let Some(parent) = info.parent {
+            for item in self.schema.iter(parent)? {
+                if let SchemaElement::Sheet(sheet) = item {
+                    if sheet_name(sheet) != info.name {
+                        continue;
+                    }
+                    for pin in sheet.pin.iter().filter(|p| is_bus(&p.name, &self.bus_aliases)) {
+                        for member in expand_bus(&pin.name, &self.bus_aliases)? {
+                            let net = format!("{}{}", self.net_prefix(parent), member);
+                            members.insert(member, net);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(members)
+    }
+
+    /// get the netlist of all pages.
+    ///
+    /// the labels are resolved before the netlist is created. local labels
+    /// of child pages get the sheet path as prefix, global labels keep their
+    /// name and the hierarchical labels are joined with the sheet pins of the
+    /// parent page. bus labels are removed, the bus members that enter a page
+    /// through a sheet pin keep the net name of the parent page.
+    pub(crate) fn netlist(&self) -> Result<Netlist, Error> {
+        self.build_netlist(false)
+    }
+
+    /// get the netlist of all pages where the positions can be looked up by page.
+    ///
+    /// the pages are moved apart before the netlist is created, positions
+    /// are looked up with `node_name`.
+    pub(crate) fn paged_netlist(&self) -> Result<Netlist, Error> {
+        self.build_netlist(true)
+    }
+
+    fn build_netlist(&self, paged: bool) -> Result<Netlist, Error> {
+        let mut schema = self.schema.clone();
+        for page in 0..self.schema.pages.len() {
+            let prefix = self.net_prefix(page);
+            let path = self.sheet_path(page);
+            let members = self.bus_pin_members(page)?;
+            let mut labels = Vec::new();
+            for item in self.schema.iter(page)? {
+                match item {
+                    SchemaElement::GlobalLabel(label) if !is_bus(&label.text, &self.bus_aliases) => {
+                        let name = label.text.as_str();
+                        labels.push(Label::new(label.at.clone(), label.angle, name, uuid!()));
+                    }
+                    SchemaElement::HierarchicalLabel(label)
+                        if !is_bus(&label.text, &self.bus_aliases) =>
+                    {
+                        let name = format!("{}{}", path, label.text);
+                        labels.push(Label::new(label.at.clone(), label.angle, name.as_str(), uuid!()));
+                    }
+                    SchemaElement::Sheet(sheet) => {
+                        let sheet_name = sheet_name(sheet);
+                        for pin in sheet.pin.iter().filter(|p| !is_bus(&p.name, &self.bus_aliases)) {
+                            let name = format!("{}{}/{}", path, sheet_name, pin.name);
+                            labels.push(Label::new(pin.at.clone(), 0.0, name.as_str(), uuid!()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let items = &mut schema.page(page).ok_or(Error::PageNotFound(page))?.items;
+            items.retain(|item| match item {
+                SchemaElement::Label(label) => !is_bus(&label.text, &self.bus_aliases),
+                _ => true,
+            });
+            for item in items.iter_mut() {
+                if let SchemaElement::Label(label) = item {
+                    label.text = match members.get(&label.text) {
+                        Some(net) => net.to_string(),
+                        None => format!("{}{}", prefix, label.text),
+                    };
+                }
+            }
+            items.extend(labels.into_iter().map(SchemaElement::Label));
+            if paged {
+                for item in items.iter_mut() {
+                    shift(item, page as f64 * PAGE_OFFSET);
+                }
+            }
+        }
+        Ok(Netlist::from(&schema)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::Draw;
+
+    #[test]
+    fn page_without_sheet() {
+        let mut draw = Draw::new(vec![], 1.27).unwrap();
+        // a page that is not known as sheet, like an extra page of a loaded schema.
+        draw.schema.new_page();
+        assert!(matches!(draw.netlist(), Err(Error::PageNotFound(1))));
+        assert!(matches!(draw.paged_netlist(), Err(Error::PageNotFound(1))));
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use elektron_sexp::{Bounds, SchemaElement};
+use elektron_spice::{self as spice, Netlist};
+use ndarray::arr1;
+use pyo3::prelude::*;
+
+use crate::circuit::Circuit;
+use crate::erc::{Connectivity, Key};
+use crate::error::Error;
+use crate::netlist::node_name;
+use crate::annotate::{self, split_reference};
+use crate::{orientation, Draw};
+
+/// the font size of the voltage and current tags.
+const TAG_SIZE: f64 = 1.0;
+const VOLTAGE_COLOR: &str = "#d62728";
+const CURRENT_COLOR: &str = "#1f77b4";
+
+/// get the operating point from a simulation result or run it for the circuit.
+///
+/// the values of the result can be numbers or the vectors of the analysis.
+pub fn extract_op(item: &'_ PyAny) -> PyResult<HashMap<String, f64>> {
+    let result: HashMap<String, Vec<f64>> = if let Ok(circuit) = item.extract::<Circuit>() {
+        spice::Simulation::new(circuit.circuit).op()
+    } else if let Ok(result) = item.extract::<HashMap<String, f64>>() {
+        return Ok(result.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect());
+    } else {
+        item.extract()?
+    };
+    Ok(result
+        .into_iter()
+        .filter_map(|(k, v)| v.first().map(|v| (k.to_lowercase(), *v)))
+        .collect())
+}
+
+/// format the value with a si prefix.
+fn si(value: f64, unit: &str) -> String {
+    const PREFIXES: [(f64, &str); 7] = [
+        (1e6, "M"),
+        (1e3, "k"),
+        (1.0, ""),
+        (1e-3, "m"),
+        (1e-6, "µ"),
+        (1e-9, "n"),
+        (1e-12, "p"),
+    ];
+    if value == 0.0 {
+        return format!("0 {}", unit);
+    }
+    let (factor, prefix) = PREFIXES
+        .iter()
+        .find(|(factor, _)| value.abs() >= *factor)
+        .copied()
+        .unwrap_or(PREFIXES[PREFIXES.len() - 1]);
+    format!("{:.3} {}{}", value / factor, prefix, unit)
+}
+
+/// parse a spice value with scale factor, `4k7` is the same as `4.7k`.
+fn spice_value(value: &str) -> Option<f64> {
+    const SCALES: [(&str, f64); 10] = [
+        ("meg", 1e6),
+        ("mil", 25.4e-6),
+        ("t", 1e12),
+        ("g", 1e9),
+        ("k", 1e3),
+        ("m", 1e-3),
+        ("u", 1e-6),
+        ("n", 1e-9),
+        ("p", 1e-12),
+        ("f", 1e-15),
+    ];
+    let value = value.trim().to_lowercase().replace('µ', "u");
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+' || c == 'e'))
+        .unwrap_or(value.len());
+    // an exponent needs digits after the e.
+    let end = if value[..end].ends_with('e') { end - 1 } else { end };
+    let (number, rest) = value.split_at(end);
+    let (factor, rest) = SCALES
+        .iter()
+        .find(|(suffix, _)| rest.starts_with(suffix))
+        .map(|(suffix, factor)| (*factor, &rest[suffix.len()..]))
+        .unwrap_or((1.0, rest));
+    // the digits after the scale factor are the decimals.
+    let decimals: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let number = if decimals.is_empty() {
+        number.to_string()
+    } else {
+        format!("{}.{}", number, decimals)
+    };
+    number.parse::<f64>().ok().map(|v| v * factor)
+}
+
+fn lookup(op: &HashMap<String, f64>, names: &[String]) -> Option<f64> {
+    names.iter().find_map(|name| op.get(name)).copied()
+}
+
+/// get the voltage of the ngspice node, the ground node is not in the result.
+fn node_voltage(op: &HashMap<String, f64>, name: &str) -> Option<f64> {
+    let name = name.to_lowercase();
+    if name == "0" || name == "gnd" {
+        return Some(0.0);
+    }
+    lookup(op, &[name.to_string(), format!("v({})", name)])
+}
+
+fn text(svg: &mut String, x: f64, y: f64, anchor: &str, color: &str, value: &str) -> std::fmt::Result {
+    write!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"{}\" fill=\"{}\">{}</text>",
+        x, y, TAG_SIZE, anchor, color, value
+    )
+}
+
+impl Draw {
+    /// get the svg elements with the voltage and current tags of the page.
+    ///
+    /// the voltages are written on the longest wire of every net, the
+    /// net is mapped to the ngspice node with the netlist. the currents
+    /// are written next to the two pin symbols. a plain `.op` does not save
+    /// the current of resistors, it is calculated from the node voltages
+    /// and the value, positive from pin 1 to pin 2.
+    pub(crate) fn op_svg(
+        &self,
+        connectivity: &mut Connectivity,
+        netlist: &Netlist,
+        op: &HashMap<String, f64>,
+        page: usize,
+    ) -> Result<String, Error> {
+        let mut svg = String::new();
+        let length = |w: &(Key, Key)| (w.1 .1 - w.0 .1).abs() + (w.1 .2 - w.0 .2).abs();
+        let mut wires: Vec<(Key, Key)> =
+            connectivity.wires.iter().filter(|w| w.0 .0 == page).copied().collect();
+        wires.sort_by_key(|w| std::cmp::Reverse(length(w)));
+        let mut tagged: Vec<usize> = Vec::new();
+        for (start, end) in wires {
+            let node = connectivity.nets.node(start);
+            let root = connectivity.nets.find(node);
+            if tagged.contains(&root) {
+                continue;
+            }
+            tagged.push(root);
+            let pos = arr1(&[start.1 as f64 / 100.0, start.2 as f64 / 100.0]);
+            if let Some(name) = node_name(netlist, page, &pos) {
+                if let Some(voltage) = node_voltage(op, &name) {
+                    let x = (start.1 + end.1) as f64 / 200.0;
+                    let y = (start.2 + end.2) as f64 / 200.0 - TAG_SIZE / 2.0;
+                    text(&mut svg, x, y, "middle", VOLTAGE_COLOR, si(voltage, "V").as_str())?;
+                }
+            }
+        }
+
+        for item in self.schema.iter(page)? {
+            if let SchemaElement::Symbol(symbol) = item {
+                let lib = self
+                    .schema
+                    .get_library(symbol.lib_id.as_str())
+                    .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                if lib.power || lib.pins(symbol.unit)?.len() != 2 {
+                    continue;
+                }
+                let reference = annotate::reference(symbol).unwrap_or("").to_lowercase();
+                let names = [
+                    format!("@{}[i]", reference),
+                    format!("{}#branch", reference),
+                    format!("i({})", reference),
+                ];
+                let current = lookup(op, &names).or_else(|| {
+                    if split_reference(&reference).0 != "r" {
+                        return None;
+                    }
+                    let value = symbol.property.iter().find(|p| p.key == "Value")?;
+                    let resistance = spice_value(&value.value).filter(|r| *r != 0.0)?;
+                    let mut pins = lib.pins(symbol.unit).ok()?;
+                    pins.sort_by(|a, b| a.number.0.cmp(&b.number.0));
+                    let mut voltages = pins.iter().map(|pin| {
+                        let pos = orientation::symbol_transform(symbol, &pin.at).ok()?;
+                        node_voltage(op, &node_name(netlist, page, &pos)?)
+                    });
+                    Some((voltages.next()?? - voltages.next()??) / resistance)
+                });
+                if let Some(current) = current {
+                    let bounds = orientation::symbol_bounds(symbol, &symbol.bounds(lib)?)?;
+                    let y = (bounds[[0, 1]] + bounds[[1, 1]]) / 2.0 + TAG_SIZE;
+                    text(&mut svg, bounds[[1, 0]] + TAG_SIZE, y, "start", CURRENT_COLOR, si(current, "A").as_str())?;
+                }
+            }
+        }
+        Ok(svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{node_voltage, spice_value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn values() {
+        assert_eq!(spice_value("100"), Some(100.0));
+        assert_eq!(spice_value("10k"), Some(10_000.0));
+        assert_eq!(spice_value("4k7"), Some(4_700.0));
+        assert_eq!(spice_value("1Meg"), Some(1e6));
+        assert_eq!(spice_value("1e3"), Some(1_000.0));
+        assert!((spice_value("100n").unwrap() - 100e-9).abs() < 1e-18);
+        assert!((spice_value("2.2µ").unwrap() - 2.2e-6).abs() < 1e-15);
+        assert_eq!(spice_value("k"), None);
+    }
+
+    #[test]
+    fn ground_voltage() {
+        let op = HashMap::from([(String::from("v(out)"), 4.5)]);
+        assert_eq!(node_voltage(&op, "GND"), Some(0.0));
+        assert_eq!(node_voltage(&op, "0"), Some(0.0));
+        assert_eq!(node_voltage(&op, "OUT"), Some(4.5));
+        assert_eq!(node_voltage(&op, "in"), None);
+    }
+}
@@ -0,0 +1,144 @@
+use elektron_sexp::Symbol;
+use ndarray::{arr1, arr2, Array1, Array2};
+
+use crate::error::Error;
+
+/// get the number of quarter turns for the angle.
+///
+/// negative angles and angles above 360 degrees are normalized.
+pub fn quadrant(angle: f64) -> Result<usize, Error> {
+    let turns = angle / 90.0;
+    if (turns - turns.round()).abs() > 0.001 {
+        return Err(Error::InvalidValue(String::from("angle"), angle.to_string()));
+    }
+    Ok((turns.round() as i64).rem_euclid(4) as usize)
+}
+
+/// the transformation matrix from library to schema coordinates.
+///
+/// the library y axis points up, the schema y axis down. the symbol is
+/// rotated counter clockwise first and then mirrored, mirror `x` flips
+/// the y coordinates and mirror `y` the x coordinates.
+pub fn matrix(angle: f64, mirror: Option<&str>) -> Result<Array2<f64>, Error> {
+    let rotation = match quadrant(angle)? {
+        0 => arr2(&[[1., 0.], [0., 1.]]),
+        1 => arr2(&[[0., 1.], [-1., 0.]]),
+        2 => arr2(&[[-1., 0.], [0., -1.]]),
+        _ => arr2(&[[0., -1.], [1., 0.]]),
+    };
+    let mirror = match mirror {
+        None | Some("") => arr2(&[[1., 0.], [0., -1.]]),
+        Some("x") => arr2(&[[1., 0.], [0., 1.]]),
+        Some("y") => arr2(&[[-1., 0.], [0., -1.]]),
+        Some(mirror) => {
+            return Err(Error::InvalidValue(String::from("mirror"), mirror.to_string()))
+        }
+    };
+    Ok(rotation.dot(&mirror))
+}
+
+/// transform a point relative to the symbol origin.
+pub fn transform(at: &Array1<f64>, angle: f64, mirror: Option<&str>) -> Result<Array1<f64>, Error> {
+    Ok(at.dot(&matrix(angle, mirror)?))
+}
+
+/// get the absolute position of a library point of the placed symbol.
+pub fn symbol_transform(symbol: &Symbol, at: &Array1<f64>) -> Result<Array1<f64>, Error> {
+    Ok(&symbol.at + &transform(at, symbol.angle, symbol.mirror.as_deref())?)
+}
+
+/// get the absolute bounds of the placed symbol, sorted as `[[min], [max]]`.
+pub fn symbol_bounds(symbol: &Symbol, bounds: &Array2<f64>) -> Result<Array2<f64>, Error> {
+    let a = symbol_transform(symbol, &arr1(&[bounds[[0, 0]], bounds[[0, 1]]]))?;
+    let b = symbol_transform(symbol, &arr1(&[bounds[[1, 0]], bounds[[1, 1]]]))?;
+    Ok(arr2(&[
+        [a[0].min(b[0]), a[1].min(b[1])],
+        [a[0].max(b[0]), a[1].max(b[1])],
+    ]))
+}
+
+/// get the side of a pin after rotation and mirror.
+///
+/// the pin angle is counted the same way as in `Draw::pin_position`.
+pub fn pin_side(pin_angle: f64, angle: f64, mirror: Option<&str>) -> Result<usize, Error> {
+    let side = (quadrant(pin_angle)? + quadrant(angle)?) % 4;
+    Ok(match (mirror, side) {
+        (Some("x"), 1) => 3,
+        (Some("x"), 3) => 1,
+        (Some("y"), 0) => 2,
+        (Some("y"), 2) => 0,
+        _ => side,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use elektron_sexp::{Schema, SchemaElement, Shape, Symbol, Transform};
+    use ndarray::arr1;
+
+    use super::{quadrant, symbol_transform};
+
+    const SCHEMA: &str = r#"(kicad_sch (version 20211123) (generator eeschema)
+  (uuid 5c3a4a4e-7a47-4bd6-9e2b-54f2c3b1c101)
+  (paper "A4")
+  (lib_symbols)
+  (symbol (lib_id "Device:R") (at 25.4 50.8 0) (unit 1)
+    (in_bom yes) (on_board yes)
+    (uuid 5c3a4a4e-7a47-4bd6-9e2b-54f2c3b1c102)
+    (property "Reference" "R1" (id 0) (at 25.4 50.8 0)
+      (effects (font (size 1.27 1.27)))
+    )
+    (property "Value" "1k" (id 1) (at 25.4 50.8 0)
+      (effects (font (size 1.27 1.27)))
+    )
+  )
+  (sheet_instances
+    (path "/" (page "1"))
+  )
+)
+"#;
+
+    fn symbol() -> Symbol {
+        let path = std::env::temp_dir().join("elektron_orientation.kicad_sch");
+        std::fs::write(&path, SCHEMA).unwrap();
+        let schema = Schema::load(path.to_str().unwrap()).unwrap();
+        for item in schema.iter(0).unwrap() {
+            if let SchemaElement::Symbol(symbol) = item {
+                return symbol.clone();
+            }
+        }
+        panic!("no symbol in test schema");
+    }
+
+    #[test]
+    fn quadrants() {
+        assert_eq!(quadrant(0.0).unwrap(), 0);
+        assert_eq!(quadrant(90.0).unwrap(), 1);
+        assert_eq!(quadrant(-90.0).unwrap(), 3);
+        assert_eq!(quadrant(450.0).unwrap(), 1);
+        assert!(quadrant(45.0).is_err());
+    }
+    #[test]
+    fn same_as_shape_transform() {
+        let mut symbol = symbol();
+        let points = [arr1(&[0.0, 3.81]), arr1(&[-2.54, 1.27]), arr1(&[5.08, -7.62])];
+        for angle in [0.0, 90.0, 180.0, 270.0, -90.0, 450.0] {
+            for mirror in [None, Some("x"), Some("y")] {
+                symbol.angle = angle;
+                symbol.mirror = mirror.map(String::from);
+                for at in &points {
+                    let expected = Shape::transform(&symbol, at);
+                    let actual = symbol_transform(&symbol, at).unwrap();
+                    assert!(
+                        (&expected - &actual).iter().all(|d| d.abs() < 0.01),
+                        "angle {} mirror {:?}: {} != {}",
+                        angle,
+                        mirror,
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, StringFormat};
+
+use crate::error::Error;
+use crate::plotting::PlotOptions;
+use crate::Draw;
+
+/// the font size of the page title in points.
+const TITLE_SIZE: f64 = 10.0;
+/// the distance of the page title from the top left corner in points.
+const TITLE_OFFSET: (f64, f64) = (28.0, 20.0);
+/// the name of the page title font in the page resources.
+const TITLE_FONT: &str = "ElektronTitle";
+
+/// encode the text for the standard fonts, unknown characters are replaced with `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    const SPECIAL: [(char, u8); 27] = [
+        ('€', 0x80), ('‚', 0x82), ('ƒ', 0x83), ('„', 0x84), ('…', 0x85), ('†', 0x86),
+        ('‡', 0x87), ('ˆ', 0x88), ('‰', 0x89), ('Š', 0x8a), ('‹', 0x8b), ('Œ', 0x8c),
+        ('Ž', 0x8e), ('‘', 0x91), ('’', 0x92), ('“', 0x93), ('”', 0x94), ('•', 0x95),
+        ('–', 0x96), ('—', 0x97), ('˜', 0x98), ('™', 0x99), ('š', 0x9a), ('›', 0x9b),
+        ('œ', 0x9c), ('ž', 0x9e), ('Ÿ', 0x9f),
+    ];
+    text.chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7e | 0xa0..=0xff => c as u8,
+            _ => SPECIAL.iter().find(|(s, _)| *s == c).map_or(b'?', |(_, b)| *b),
+        })
+        .collect()
+}
+
+/// get the pages depth first, the child sheets follow their parent page.
+fn hierarchy_order(parents: &[Option<usize>]) -> Vec<usize> {
+    fn walk(parents: &[Option<usize>], parent: Option<usize>, order: &mut Vec<usize>) {
+        for (page, _) in parents.iter().enumerate().filter(|(_, p)| **p == parent) {
+            order.push(page);
+            walk(parents, Some(page), order);
+        }
+    }
+    let mut order = Vec::new();
+    walk(parents, None, &mut order);
+    order
+}
+
+/// write the title on the top left corner of the page.
+fn page_title(document: &mut Document, page: ObjectId, title: &str) -> Result<(), Error> {
+    let height = document
+        .get_dictionary(page)?
+        .get(b"MediaBox")
+        .and_then(|media_box| media_box.as_array())
+        .ok()
+        .and_then(|media_box| media_box.get(3))
+        .and_then(|height| height.as_float().ok())
+        .unwrap_or(595.0) as f64;
+
+    let font = document.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let shared = match document.get_or_create_resources(page)?.as_dict()?.get(b"Font") {
+        Ok(Object::Reference(id)) => Some(*id),
+        _ => None,
+    };
+    if let Some(id) = shared {
+        document.get_object_mut(id)?.as_dict_mut()?.set(TITLE_FONT, font);
+    } else {
+        let resources = document.get_or_create_resources(page)?.as_dict_mut()?;
+        let mut fonts = match resources.get(b"Font") {
+            Ok(Object::Dictionary(fonts)) => fonts.clone(),
+            _ => Dictionary::new(),
+        };
+        fonts.set(TITLE_FONT, font);
+        resources.set("Font", fonts);
+    }
+
+    let content = Content {
+        operations: vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![TITLE_FONT.into(), TITLE_SIZE.into()]),
+            Operation::new("Td", vec![TITLE_OFFSET.0.into(), (height - TITLE_OFFSET.1).into()]),
+            Operation::new("Tj", vec![Object::String(win_ansi(title), StringFormat::Literal)]),
+            Operation::new("ET", vec![]),
+        ],
+    };
+    document.add_page_contents(page, content.encode()?)?;
+    Ok(())
+}
+
+/// merge the single page documents in order.
+fn merge(documents: Vec<(String, Vec<u8>)>) -> Result<Document, Error> {
+    let mut merged: Option<(Document, ObjectId)> = None;
+    for (title, buffer) in documents {
+        let mut document = Document::load_mem(&buffer)?;
+        if let Some((target, pages_id)) = &mut merged {
+            document.renumber_objects_with(target.max_id + 1);
+            let pages: Vec<ObjectId> = document.get_pages().into_values().collect();
+            for page in &pages {
+                page_title(&mut document, *page, &title)?;
+                document
+                    .get_object_mut(*page)?
+                    .as_dict_mut()?
+                    .set("Parent", Object::Reference(*pages_id));
+            }
+            // the catalog and page tree are replaced by the ones of the target.
+            let root = document.trailer.get(b"Root")?.as_reference()?;
+            let tree = document.catalog()?.get(b"Pages")?.as_reference()?;
+            document.objects.remove(&root);
+            document.objects.remove(&tree);
+            target.max_id = document.max_id;
+            target.objects.extend(document.objects);
+
+            let tree = target.get_object_mut(*pages_id)?.as_dict_mut()?;
+            let mut kids = tree.get(b"Kids")?.as_array()?.clone();
+            kids.extend(pages.iter().map(|p| Object::Reference(*p)));
+            tree.set("Count", kids.len() as i64);
+            tree.set("Kids", kids);
+        } else {
+            for page in document.get_pages().into_values() {
+                page_title(&mut document, page, &title)?;
+            }
+            let pages_id = document.catalog()?.get(b"Pages")?.as_reference()?;
+            merged = Some((document, pages_id));
+        }
+    }
+    merged
+        .map(|(document, _)| document)
+        .ok_or_else(|| Error::Plot(String::from("no pages to export")))
+}
+
+impl Draw {
+    /// plot all pages with the title block and merge them to one pdf.
+    ///
+    /// the pages are in hierarchy order, every child sheet follows its
+    /// parent page. every page gets the page name as title. the pdf always
+    /// contains the full pages, the crop and overlay options are not used.
+    /// theme files only style svg images, the pdf needs a built in theme.
+    pub(crate) fn pdf(&self, options: Option<PlotOptions>) -> Result<Vec<u8>, Error> {
+        let mut options = match options {
+            Some(options) => options,
+            None => PlotOptions::from_env()?,
+        };
+        if options.theme.css().is_some() {
+            return Err(Error::Plot(format!(
+                "the theme {} is a theme file, the pdf can only use the built in themes",
+                options.theme.name
+            )));
+        }
+        options.format = String::from("pdf");
+        options.border = true;
+        options.pages = None;
+        options.references = None;
+        options.area = None;
+        options.highlight = Vec::new();
+        options.operating_point = None;
+        let mut pages: Vec<Option<(String, Vec<u8>)>> =
+            self.plot_pages(&options)?.into_iter().map(Some).collect();
+        let parents: Vec<Option<usize>> = self.pages.iter().map(|info| info.parent).collect();
+        let ordered = hierarchy_order(&parents)
+            .into_iter()
+            .filter_map(|page| pages.get_mut(page).and_then(Option::take))
+            .collect();
+        let mut document = merge(ordered)?;
+        document.compress();
+        let mut buffer = Vec::new();
+        document.save_to(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hierarchy_order, merge, win_ansi};
+    use lopdf::{dictionary, Document, Object, Stream};
+
+    /// create a pdf with one empty page.
+    fn page() -> Vec<u8> {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let content_id = document.add_object(Stream::new(dictionary! {}, Vec::new()));
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 842.into(), 595.into()],
+            "Contents" => content_id,
+        });
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+        let mut buffer = Vec::new();
+        document.save_to(&mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn merge_pages() {
+        let documents = vec![
+            (String::from("root"), page()),
+            (String::from("filter"), page()),
+            (String::from("output"), page()),
+        ];
+        let mut buffer = Vec::new();
+        merge(documents).unwrap().save_to(&mut buffer).unwrap();
+        let document = Document::load_mem(&buffer).unwrap();
+
+        let pages = document.get_pages();
+        assert_eq!(3, pages.len());
+        let pages_id = document.catalog().unwrap().get(b"Pages").unwrap().as_reference().unwrap();
+        let tree = document.get_dictionary(pages_id).unwrap();
+        assert_eq!(3, tree.get(b"Count").unwrap().as_i64().unwrap());
+        let kids = tree.get(b"Kids").unwrap().as_array().unwrap();
+        assert_eq!(3, kids.len());
+        for page in pages.values() {
+            let page = document.get_dictionary(*page).unwrap();
+            assert_eq!(pages_id, page.get(b"Parent").unwrap().as_reference().unwrap());
+        }
+    }
+
+    #[test]
+    fn merge_nothing() {
+        assert!(merge(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn title_encoding() {
+        assert_eq!(b"Filter".to_vec(), win_ansi("Filter"));
+        assert_eq!(vec![b'F', 0xfc, 0xdf, b'e', b' ', 0x80], win_ansi("Füße €"));
+        assert_eq!(b"?".to_vec(), win_ansi("Ω"));
+    }
+
+    #[test]
+    fn depth_first() {
+        // the child sheet of page 1 was created after the child sheet of page 2.
+        let parents = [None, Some(0), Some(0), Some(2), Some(1)];
+        assert_eq!(vec![0, 1, 4, 2, 3], hierarchy_order(&parents));
+    }
+}
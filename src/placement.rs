@@ -0,0 +1,291 @@
+use elektron_sexp::{Bounds, Effects, Property, SchemaElement, Symbol};
+use itertools::Itertools;
+use ndarray::{arr1, Array1, Array2};
+
+use crate::error::Error;
+use crate::orientation;
+use crate::{label_justify, Draw, LABEL_BORDER};
+
+/// approximate width of a character with the default font size.
+const CHAR_WIDTH: f64 = 1.27;
+/// approximate height of a text line with the default font size.
+const TEXT_HEIGHT: f64 = 1.27;
+/// half of the width used for wires when checking collisions.
+const WIRE_WIDTH: f64 = 0.25;
+
+/// the side of the symbol where the fields are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    North,
+    East,
+    West,
+    South,
+}
+
+impl Side {
+    pub fn from(name: &str) -> Result<Self, Error> {
+        match name {
+            "north" => Ok(Side::North),
+            "east" => Ok(Side::East),
+            "west" => Ok(Side::West),
+            "south" => Ok(Side::South),
+            _ => Err(Error::InvalidValue(String::from("fields"), name.to_string())),
+        }
+    }
+
+    /// the side opposite of the pins at `pin_position` index.
+    fn opposite(index: usize) -> Self {
+        [Side::East, Side::North, Side::West, Side::South][index]
+    }
+
+    /// the `pin_position` index of the pins on this side.
+    fn index(&self) -> usize {
+        match self {
+            Side::North => 3,
+            Side::East => 2,
+            Side::West => 0,
+            Side::South => 1,
+        }
+    }
+}
+
+/// a rectangle as `[x0, y0, x1, y1]`.
+pub(crate) type Rect = [f64; 4];
+
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3]
+}
+
+/// get the approximate box of a text.
+///
+/// text at 90 and 270 degrees is vertical and read from the bottom,
+/// the start of left justified text is at the bottom.
+pub(crate) fn text_box(at: &Array1<f64>, text: &str, justify: &[String], angle: f64) -> Rect {
+    let width = text.chars().count() as f64 * CHAR_WIDTH;
+    let start = if justify.iter().any(|j| j == "left") {
+        0.0
+    } else if justify.iter().any(|j| j == "right") {
+        -width
+    } else {
+        -width / 2.0
+    };
+    if ((angle / 90.0).round() as i64).rem_euclid(2) == 1 {
+        [at[0] - TEXT_HEIGHT / 2.0, at[1] - start - width, at[0] + TEXT_HEIGHT / 2.0, at[1] - start]
+    } else {
+        [at[0] + start, at[1] - TEXT_HEIGHT / 2.0, at[0] + start + width, at[1] + TEXT_HEIGHT / 2.0]
+    }
+}
+
+pub(crate) fn visible(property: &Property) -> bool {
+    if let Some(effects) = &property.effects {
+        !effects.hide
+    } else {
+        true
+    }
+}
+
+/// A field position proposed by the placement.
+struct Field {
+    at: Array1<f64>,
+    angle: f64,
+    justify: Option<&'static str>,
+    rect: Rect,
+}
+
+/// get the field positions for the side.
+fn layout(symbol: &Symbol, bounds: &Array2<f64>, side: Side) -> Vec<Field> {
+    let fields: Vec<&Property> = symbol
+        .property
+        .iter()
+        .filter(|p| visible(p))
+        .sorted_by(|a, b| a.id.cmp(&b.id))
+        .collect();
+    let count = fields.len() as f64;
+    let middle = bounds[[0, 1]] + (bounds[[1, 1]] - bounds[[0, 1]]) / 2.0
+        - ((count - 1.0) * LABEL_BORDER) / 2.0;
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, property)| {
+            let offset = i as f64 * LABEL_BORDER;
+            let (at, angle, justify) = match side {
+                Side::North => (
+                    arr1(&[symbol.at[0], bounds[[0, 1]] - count * LABEL_BORDER + offset]),
+                    0.0 - symbol.angle,
+                    None,
+                ),
+                Side::South => (
+                    arr1(&[symbol.at[0], bounds[[1, 1]] + LABEL_BORDER + offset]),
+                    0.0 - symbol.angle,
+                    None,
+                ),
+                Side::East => (
+                    arr1(&[bounds[[1, 0]] + LABEL_BORDER / 2.0, middle + offset]),
+                    360.0 - symbol.angle,
+                    Some("left"),
+                ),
+                Side::West => (
+                    arr1(&[bounds[[0, 0]] - LABEL_BORDER / 2.0, middle + offset]),
+                    360.0 - symbol.angle,
+                    Some("right"),
+                ),
+            };
+            let justify_list: Vec<String> = justify.iter().map(|j| j.to_string()).collect();
+            let rect = text_box(&at, &property.value, &justify_list, angle);
+            Field { at, angle, justify, rect }
+        })
+        .collect()
+}
+
+impl Draw {
+    /// collect the boxes of the items on the current page the fields should not cover.
+    fn field_obstacles(&self) -> Result<Vec<Rect>, Error> {
+        let mut obstacles = Vec::new();
+        for item in self.schema.iter(self.page)? {
+            match item {
+                SchemaElement::Symbol(symbol) => {
+                    let lib = self
+                        .schema
+                        .get_library(symbol.lib_id.as_str())
+                        .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                    let bounds = orientation::symbol_bounds(symbol, &symbol.bounds(lib)?)?;
+                    obstacles.push([bounds[[0, 0]], bounds[[0, 1]], bounds[[1, 0]], bounds[[1, 1]]]);
+                    for property in symbol.property.iter().filter(|p| visible(p)) {
+                        let justify = property
+                            .effects
+                            .as_ref()
+                            .map(|e| e.justify.clone())
+                            .unwrap_or_default();
+                        obstacles.push(text_box(&property.at, &property.value, &justify, property.angle));
+                    }
+                }
+                SchemaElement::Wire(wire) => {
+                    obstacles.push([
+                        wire.pts[[0, 0]].min(wire.pts[[1, 0]]) - WIRE_WIDTH,
+                        wire.pts[[0, 1]].min(wire.pts[[1, 1]]) - WIRE_WIDTH,
+                        wire.pts[[0, 0]].max(wire.pts[[1, 0]]) + WIRE_WIDTH,
+                        wire.pts[[0, 1]].max(wire.pts[[1, 1]]) + WIRE_WIDTH,
+                    ]);
+                }
+                SchemaElement::Label(label) => {
+                    let justify = [label_justify(label.angle)];
+                    obstacles.push(text_box(&label.at, &label.text, &justify, label.angle));
+                }
+                SchemaElement::GlobalLabel(label) => {
+                    let justify = [label_justify(label.angle)];
+                    obstacles.push(text_box(&label.at, &label.text, &justify, label.angle));
+                }
+                SchemaElement::HierarchicalLabel(label) => {
+                    let justify = [label_justify(label.angle)];
+                    obstacles.push(text_box(&label.at, &label.text, &justify, label.angle));
+                }
+                SchemaElement::Text(text) => {
+                    obstacles.push(text_box(&text.at, &text.text, &text.effects.justify, text.angle));
+                }
+                _ => {}
+            }
+        }
+        Ok(obstacles)
+    }
+
+    /// place the visible fields of the symbol.
+    ///
+    /// the sides without pins are tried first, the first side where the
+    /// fields do not collide with other items is used. when all sides
+    /// collide the side with the fewest collisions is taken. a side set
+    /// on the element is used as is.
+    pub(crate) fn place_property(
+        &mut self,
+        symbol: &mut Symbol,
+        side: Option<Side>,
+    ) -> Result<(), Error> {
+        let lib = self.get_library(&symbol.lib_id)?;
+        let bounds = orientation::symbol_bounds(symbol, &symbol.bounds(&lib)?)?;
+        let positions = self.pin_position(symbol, &lib)?;
+
+        let candidates = if let Some(side) = side {
+            vec![side]
+        } else {
+            let mut sides: Vec<Side> = [Side::North, Side::East, Side::West, Side::South]
+                .into_iter()
+                .sorted_by_key(|s| positions[s.index()])
+                .collect();
+            // the fields of single pin symbols go opposite of the pin.
+            if lib.pins(symbol.unit)?.len() == 1 {
+                if let Some(index) = positions.iter().position(|p| *p == 1) {
+                    let opposite = Side::opposite(index);
+                    sides.retain(|s| *s != opposite);
+                    sides.insert(0, opposite);
+                }
+            }
+            sides
+        };
+
+        let obstacles = self.field_obstacles()?;
+        let mut best: Option<(usize, Vec<Field>)> = None;
+        for side in candidates {
+            let fields = layout(symbol, &bounds, side);
+            let collisions: usize = fields
+                .iter()
+                .map(|f| obstacles.iter().filter(|o| overlaps(&f.rect, o)).count())
+                .sum();
+            if best.as_ref().map_or(true, |(c, _)| collisions < *c) {
+                best = Some((collisions, fields));
+            }
+            if collisions == 0 {
+                break;
+            }
+        }
+
+        if let Some((_, fields)) = best {
+            symbol
+                .property
+                .iter_mut()
+                .filter(|p| visible(p))
+                .sorted_by(|a, b| a.id.cmp(&b.id))
+                .zip(fields)
+                .for_each(|(p, field)| {
+                    let effects = p.effects.get_or_insert_with(Effects::new);
+                    effects.justify.clear();
+                    if let Some(justify) = field.justify {
+                        effects.justify.push(justify.to_string());
+                    }
+                    p.at = field.at;
+                    p.angle = field.angle;
+                });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr1;
+
+    use super::{text_box, Rect, Side};
+
+    fn assert_rect(actual: Rect, expected: Rect) {
+        assert!(actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < 1e-9), "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn opposite_side() {
+        for index in 0..4 {
+            assert_eq!(Side::opposite(index).index(), (index + 2) % 4);
+        }
+    }
+    #[test]
+    fn horizontal_text_box() {
+        let at = arr1(&[10.0, 20.0]);
+        assert_rect(text_box(&at, "R1", &[String::from("left")], 0.0), [10.0, 19.365, 12.54, 20.635]);
+        assert_rect(text_box(&at, "R1", &[String::from("right")], 180.0), [7.46, 19.365, 10.0, 20.635]);
+        assert_rect(text_box(&at, "R1", &[], 0.0), [8.73, 19.365, 11.27, 20.635]);
+    }
+    #[test]
+    fn vertical_text_box() {
+        let at = arr1(&[10.0, 20.0]);
+        assert_rect(text_box(&at, "R1", &[String::from("left")], 90.0), [9.365, 17.46, 10.635, 20.0]);
+        assert_rect(text_box(&at, "R1", &[String::from("right")], 270.0), [9.365, 20.0, 10.635, 22.54]);
+        assert_rect(text_box(&at, "R1", &[], -90.0), [9.365, 18.73, 10.635, 21.27]);
+    }
+}
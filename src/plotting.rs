@@ -0,0 +1,586 @@
+#![allow(clippy::borrow_deref_ref)]
+use std::collections::HashMap;
+use std::path::Path;
+
+use elektron_plot as plot;
+use elektron_sexp::{Bounds, Schema, SchemaElement};
+use elektron_spice::Netlist;
+use pyo3::prelude::*;
+
+use crate::error::Error;
+use crate::highlight::{highlight_svg, NetSeed};
+use crate::op::extract_op;
+use crate::placement::{text_box, visible, Rect};
+use crate::theme::Theme;
+use crate::{annotate, orientation, Draw};
+
+/// the resolution the scale of the plot is based on.
+const BASE_DPI: u32 = 96;
+/// the supported output formats.
+const FORMATS: [&str; 3] = ["svg", "png", "pdf"];
+/// the default margin around a cropped region.
+const CROP_MARGIN: f64 = 5.08;
+/// the classes of the item groups in plots with a theme file.
+const CLASSES: [&str; 9] = [
+    "wire",
+    "bus",
+    "junction",
+    "no_connect",
+    "symbol",
+    "label",
+    "global_label",
+    "hierarchical_label",
+    "text",
+];
+
+fn plot_format(format: &str) -> Result<String, Error> {
+    let format = format.to_lowercase();
+    if FORMATS.contains(&format.as_str()) {
+        Ok(format)
+    } else {
+        Err(Error::InvalidValue(String::from("format"), format))
+    }
+}
+
+/// Options for `Draw.plot`.
+///
+/// the crop options `references` and `area` and the overlays `highlight`
+/// and `operating_point` can only be used for svg images, png and pdf
+/// images raise an `ElektronError`.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct PlotOptions {
+    /// the output format: svg, png or pdf.
+    #[pyo3(get)]
+    pub format: String,
+    /// the resolution of png images.
+    #[pyo3(get, set)]
+    pub dpi: u32,
+    #[pyo3(get, set)]
+    pub scale: f64,
+    /// draw the page border and title block.
+    #[pyo3(get, set)]
+    pub border: bool,
+    /// the page numbers to plot, all pages when not set.
+    #[pyo3(get, set)]
+    pub pages: Option<Vec<usize>>,
+    /// pass the netlist to the plot to draw the net names.
+    #[pyo3(get, set)]
+    pub netlist: bool,
+    /// the theme of the plot, theme files can only be used for svg images.
+    #[pyo3(get, set)]
+    pub theme: Theme,
+    /// only plot the region around the symbols with these references,
+    /// svg images only.
+    #[pyo3(get, set)]
+    pub references: Option<Vec<String>>,
+    /// only plot the region `((x0, y0), (x1, y1))`, svg images only.
+    #[pyo3(get, set)]
+    pub area: Option<((f64, f64), (f64, f64))>,
+    /// the margin around the cropped region.
+    #[pyo3(get, set)]
+    pub margin: f64,
+    /// the nets to highlight, by name or by a `(reference, pin)` on the net,
+    /// svg images only.
+    pub highlight: Vec<NetSeed>,
+    /// the node voltages and branch currents of an operating point analysis,
+    /// svg images only.
+    pub operating_point: Option<HashMap<String, f64>>,
+}
+
+#[pymethods]
+impl PlotOptions {
+    #[new]
+    #[args(
+        format = "\"svg\"",
+        dpi = "96",
+        scale = "1.0",
+        border = "false",
+        netlist = "false",
+        margin = "CROP_MARGIN"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        format: &str,
+        dpi: u32,
+        scale: f64,
+        border: bool,
+        pages: Option<Vec<usize>>,
+        netlist: bool,
+        theme: Option<Theme>,
+        references: Option<Vec<String>>,
+        area: Option<((f64, f64), (f64, f64))>,
+        margin: f64,
+        highlight: Option<Vec<&'_ PyAny>>,
+        operating_point: Option<&'_ PyAny>,
+    ) -> PyResult<Self> {
+        let highlight = highlight
+            .unwrap_or_default()
+            .into_iter()
+            .map(NetSeed::extract)
+            .collect::<PyResult<Vec<NetSeed>>>()?;
+        let operating_point = operating_point.map(extract_op).transpose()?;
+        Ok(Self {
+            format: plot_format(format)?,
+            dpi,
+            scale,
+            border,
+            pages,
+            netlist,
+            theme: match theme {
+                Some(theme) => theme,
+                None => Theme::from_env()?.unwrap_or_default(),
+            },
+            references,
+            area,
+            margin,
+            highlight,
+            operating_point,
+        })
+    }
+
+    #[setter]
+    pub fn set_format(&mut self, format: &str) -> Result<(), Error> {
+        self.format = plot_format(format)?;
+        Ok(())
+    }
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            format: String::from("svg"),
+            dpi: BASE_DPI,
+            scale: 1.0,
+            border: false,
+            pages: None,
+            netlist: false,
+            theme: Theme::default(),
+            references: None,
+            area: None,
+            margin: CROP_MARGIN,
+            highlight: Vec::new(),
+            operating_point: None,
+        }
+    }
+}
+
+impl PlotOptions {
+    /// get the default options with the theme of the environment.
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self {
+            theme: Theme::from_env()?.unwrap_or_default(),
+            ..Self::default()
+        })
+    }
+
+    /// get the options for the file, the format is taken from the extension.
+    ///
+    /// files without an extension are plotted as svg.
+    pub fn for_file(filename: &str) -> Result<Self, Error> {
+        let mut options = Self::from_env()?;
+        if let Some(extension) = Path::new(filename).extension() {
+            options.format = plot_format(&extension.to_string_lossy())?;
+        }
+        Ok(options)
+    }
+
+    /// get the options for the notebook display.
+    pub fn notebook(format: &str, pages: Option<Vec<usize>>) -> Result<Self, Error> {
+        Ok(Self {
+            format: format.to_string(),
+            pages,
+            ..Self::from_env()?
+        })
+    }
+}
+
+impl Draw {
+    /// get the name of the page, the sheet name for child pages.
+    pub(crate) fn page_name(&self, page: usize) -> String {
+        self.pages
+            .get(page)
+            .map(|info| info.name.to_string())
+            .unwrap_or_else(|| format!("page{}", page + 1))
+    }
+
+    /// plot the selected pages and return the page names with the images.
+    pub(crate) fn plot_pages(&self, options: &PlotOptions) -> Result<Vec<(String, Vec<u8>)>, Error> {
+        if let Some(pages) = &options.pages {
+            if let Some(page) = pages.iter().find(|p| **p >= self.schema.pages.len()) {
+                return Err(Error::PageNotFound(*page));
+            }
+        }
+        let netlist = if options.netlist {
+            Some(self.netlist()?)
+        } else {
+            None
+        };
+        // the pixel size of png images follows the resolution.
+        let scale = if options.format == "png" {
+            options.scale * options.dpi as f64 / BASE_DPI as f64
+        } else {
+            options.scale
+        };
+        let crop = options.references.is_some() || options.area.is_some();
+        let overlay = !options.highlight.is_empty() || options.operating_point.is_some();
+        if (crop || overlay) && options.format != "svg" {
+            return Err(Error::Plot(String::from(
+                "only svg images can be cropped or annotated",
+            )));
+        }
+        let css = options.theme.css();
+        if css.is_some() && options.format != "svg" {
+            return Err(Error::Plot(format!(
+                "the theme {} can only be used for svg images",
+                options.theme.name
+            )));
+        }
+        if let Some(references) = &options.references {
+            if let Some(reference) = references.iter().find(|r| self.first_symbol(r).is_none()) {
+                return Err(Error::SymbolNotFound(reference.to_string()));
+            }
+        }
+
+        // select the pages and the areas before the pages are plotted.
+        let mut selected: Vec<(usize, Option<Rect>)> = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            if !options.pages.as_ref().map_or(true, |p| p.contains(&page)) {
+                continue;
+            }
+            let area = if let Some(((x0, y0), (x1, y1))) = options.area {
+                Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+            } else if let Some(references) = &options.references {
+                match self.symbols_area(page, references)? {
+                    Some(area) => Some(area),
+                    // the symbols are on other pages.
+                    None => continue,
+                }
+            } else {
+                None
+            };
+            selected.push((page, area));
+        }
+        if let (Some(references), true) = (&options.references, selected.is_empty()) {
+            // the symbols are only on pages that are not plotted.
+            return Err(Error::SymbolNotFound(references.join(", ")));
+        }
+        let mut schema = self.schema.clone();
+        schema.pages = selected.iter().map(|(page, _)| self.schema.pages[*page].clone()).collect();
+        let buffers = if css.is_some() {
+            plot_items(&schema, scale, options.border, options.theme.plotter_theme(), netlist)?
+        } else {
+            plot::plot_schema_buffer(
+                &schema,
+                scale,
+                options.border,
+                options.theme.plotter_theme(),
+                netlist,
+                options.format.as_str(),
+            )?
+        };
+
+        let highlight = if options.highlight.is_empty() {
+            None
+        } else {
+            Some(self.highlighted_nets(&options.highlight)?)
+        };
+        let mut operating_point = if let Some(result) = &options.operating_point {
+            Some((self.connectivity()?, self.paged_netlist()?, result))
+        } else {
+            None
+        };
+
+        let mut pages = Vec::new();
+        for ((page, area), buffer) in selected.into_iter().zip(buffers) {
+            let buffer = if let Some(css) = &css {
+                style_svg(&buffer, css)?
+            } else {
+                buffer
+            };
+            let buffer = if overlay {
+                let mut elements = String::new();
+                if let Some(highlight) = &highlight {
+                    elements.push_str(highlight_svg(highlight, page)?.as_str());
+                }
+                if let Some((connectivity, netlist, result)) = &mut operating_point {
+                    elements.push_str(self.op_svg(connectivity, netlist, result, page)?.as_str());
+                }
+                overlay_svg(&buffer, elements.as_str())?
+            } else {
+                buffer
+            };
+            let buffer = if let Some(area) = area {
+                let margin = options.margin;
+                crop_svg(
+                    &buffer,
+                    &[area[0] - margin, area[1] - margin, area[2] + margin, area[3] + margin],
+                )?
+            } else {
+                buffer
+            };
+            pages.push((self.page_name(page), buffer));
+        }
+        Ok(pages)
+    }
+
+    /// get the region of the symbols and their fields on the page.
+    fn symbols_area(&self, page: usize, references: &[String]) -> Result<Option<Rect>, Error> {
+        let mut area: Option<Rect> = None;
+        for item in self.schema.iter(page)? {
+            if let SchemaElement::Symbol(symbol) = item {
+                let reference = annotate::reference(symbol).unwrap_or("");
+                if !references.iter().any(|r| r == reference) {
+                    continue;
+                }
+                let lib = self
+                    .schema
+                    .get_library(symbol.lib_id.as_str())
+                    .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                let bounds = orientation::symbol_bounds(symbol, &symbol.bounds(lib)?)?;
+                let mut rects = vec![[bounds[[0, 0]], bounds[[0, 1]], bounds[[1, 0]], bounds[[1, 1]]]];
+                for property in symbol.property.iter().filter(|p| visible(p)) {
+                    let justify = property
+                        .effects
+                        .as_ref()
+                        .map(|e| e.justify.clone())
+                        .unwrap_or_default();
+                    rects.push(text_box(&property.at, &property.value, &justify, property.angle));
+                }
+                for rect in rects {
+                    area = Some(match area {
+                        Some(a) => [a[0].min(rect[0]), a[1].min(rect[1]), a[2].max(rect[2]), a[3].max(rect[3])],
+                        None => rect,
+                    });
+                }
+            }
+        }
+        Ok(area)
+    }
+
+    /// write the plotted pages to the file.
+    ///
+    /// with more than one page the page name is added to the filename.
+    pub(crate) fn write_pages(&self, filename: &str, pages: &[(String, Vec<u8>)]) -> Result<(), Error> {
+        if pages.len() == 1 {
+            std::fs::write(filename, &pages[0].1)?;
+            return Ok(());
+        }
+        for (name, buffer) in pages {
+            std::fs::write(page_filename(filename, name), buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// add the page name to the filename, `plot.svg` becomes `plot-name.svg`.
+fn page_filename(filename: &str, name: &str) -> String {
+    let path = Path::new(filename);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let file = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, name, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, name),
+    };
+    path.with_file_name(file).to_string_lossy().to_string()
+}
+
+/// get the range of the attribute value in the tag.
+fn attribute(tag: &str, name: &str) -> Option<(usize, usize)> {
+    let key = format!(" {}=\"", name);
+    let start = tag.find(key.as_str())? + key.len();
+    let end = start + tag[start..].find('"')?;
+    Some((start, end))
+}
+
+/// split a length like `297mm` in the number and the unit.
+fn split_unit(value: &str) -> Option<(f64, &str)> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+    Some((value[..end].parse().ok()?, &value[end..]))
+}
+
+/// crop the svg image to the area in schema coordinates.
+///
+/// the view box of the plot is in mm, it is replaced with the area and
+/// the image size is reduced by the same factor. the image size must be
+/// in mm and have the same ratio as the view box, otherwise the view box
+/// is not in schema coordinates.
+fn crop_svg(buffer: &[u8], area: &Rect) -> Result<Vec<u8>, Error> {
+    let svg = String::from_utf8_lossy(buffer).to_string();
+    let start = svg
+        .find("<svg")
+        .ok_or_else(|| Error::Plot(String::from("no svg element in plot")))?;
+    let end = start + svg[start..].find('>').unwrap_or(svg.len() - start);
+    let mut tag = svg[start..end].to_string();
+
+    let (vb_start, vb_end) = attribute(&tag, "viewBox")
+        .ok_or_else(|| Error::Plot(String::from("no view box in plot")))?;
+    let view_box: Vec<f64> = tag[vb_start..vb_end]
+        .split(|c: char| c == ' ' || c == ',')
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    if view_box.len() != 4 {
+        return Err(Error::Plot(format!("invalid view box: {}", &tag[vb_start..vb_end])));
+    }
+    let size: Vec<(f64, String)> = ["width", "height"]
+        .iter()
+        .filter_map(|name| attribute(&tag, name))
+        .filter_map(|(start, end)| split_unit(&tag[start..end]).map(|(v, u)| (v, u.to_string())))
+        .collect();
+    if size.len() != 2
+        || size.iter().any(|(_, unit)| unit != "mm")
+        || ((size[0].0 / size[1].0) - (view_box[2] / view_box[3])).abs() > 0.001
+    {
+        return Err(Error::Plot(String::from("the view box of the plot is not in mm")));
+    }
+    let (width, height) = (area[2] - area[0], area[3] - area[1]);
+    tag.replace_range(
+        vb_start..vb_end,
+        format!("{} {} {} {}", area[0], area[1], width, height).as_str(),
+    );
+    for (name, size, original) in [("width", width, view_box[2]), ("height", height, view_box[3])] {
+        if let Some((value_start, value_end)) = attribute(&tag, name) {
+            if let Some((value, unit)) = split_unit(&tag[value_start..value_end]) {
+                let value = format!("{}{}", value * size / original, unit);
+                tag.replace_range(value_start..value_end, value.as_str());
+            }
+        }
+    }
+    Ok(format!("{}{}{}", &svg[..start], tag, &svg[end..]).into_bytes())
+}
+
+/// get the class of the item group in plots with a theme file.
+fn item_class(item: &SchemaElement) -> Option<&'static str> {
+    match item {
+        SchemaElement::Wire(_) => Some("wire"),
+        SchemaElement::Bus(_) | SchemaElement::BusEntry(_) => Some("bus"),
+        SchemaElement::Junction(_) => Some("junction"),
+        SchemaElement::NoConnect(_) => Some("no_connect"),
+        SchemaElement::Symbol(_) => Some("symbol"),
+        SchemaElement::Label(_) => Some("label"),
+        SchemaElement::GlobalLabel(_) => Some("global_label"),
+        SchemaElement::HierarchicalLabel(_) => Some("hierarchical_label"),
+        SchemaElement::Text(_) => Some("text"),
+        _ => None,
+    }
+}
+
+/// plot the pages as svg with every item class in its own group.
+///
+/// the plotter writes no classes. the items of every class are plotted
+/// alone and added to the plot of the other items as `<g class="wire">`,
+/// the style sheet of the theme selects the groups. the border is only
+/// drawn once, the view box does not move the schema coordinates.
+fn plot_items(
+    schema: &Schema,
+    scale: f64,
+    border: bool,
+    theme: &str,
+    netlist: Option<Netlist>,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let mut other = schema.clone();
+    for page in other.pages.iter_mut() {
+        page.items.retain(|item| item_class(item).is_none());
+    }
+    let mut buffers = plot::plot_schema_buffer(&other, scale, border, theme, netlist, "svg")?;
+    for class in CLASSES {
+        let mut items = schema.clone();
+        for page in items.pages.iter_mut() {
+            page.items.retain(|item| item_class(item) == Some(class));
+        }
+        if items.pages.iter().all(|page| page.items.is_empty()) {
+            continue;
+        }
+        let groups = plot::plot_schema_buffer(&items, scale, false, theme, None, "svg")?;
+        for (buffer, group) in buffers.iter_mut().zip(groups) {
+            *buffer = group_svg(buffer, class, &svg_content(&group)?)?;
+        }
+    }
+    Ok(buffers)
+}
+
+/// get the content of the svg element.
+fn svg_content(buffer: &[u8]) -> Result<String, Error> {
+    let svg = String::from_utf8_lossy(buffer).to_string();
+    let start = svg
+        .find("<svg")
+        .and_then(|start| svg[start..].find('>').map(|end| start + end + 1))
+        .ok_or_else(|| Error::Plot(String::from("no svg element in plot")))?;
+    let end = svg
+        .rfind("</svg>")
+        .filter(|end| *end >= start)
+        .ok_or_else(|| Error::Plot(String::from("no svg element in plot")))?;
+    Ok(svg[start..end].to_string())
+}
+
+/// add the svg elements as group with the class to the plot.
+fn group_svg(buffer: &[u8], class: &str, elements: &str) -> Result<Vec<u8>, Error> {
+    let svg = String::from_utf8_lossy(buffer).to_string();
+    let end = svg
+        .rfind("</svg>")
+        .ok_or_else(|| Error::Plot(String::from("no svg element in plot")))?;
+    Ok(format!("{}<g class=\"{}\">{}</g>{}", &svg[..end], class, elements, &svg[end..]).into_bytes())
+}
+
+/// add the style sheet to the plot.
+fn style_svg(buffer: &[u8], css: &str) -> Result<Vec<u8>, Error> {
+    let svg = String::from_utf8_lossy(buffer).to_string();
+    let start = svg
+        .find("<svg")
+        .ok_or_else(|| Error::Plot(String::from("no svg element in plot")))?;
+    let end = start + svg[start..].find('>').map(|end| end + 1).unwrap_or(svg.len() - start);
+    Ok(format!("{}<style>{}</style>{}", &svg[..end], css, &svg[end..]).into_bytes())
+}
+
+/// add the svg elements on top of the plot.
+fn overlay_svg(buffer: &[u8], elements: &str) -> Result<Vec<u8>, Error> {
+    group_svg(buffer, "overlay", elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crop_svg, group_svg, page_filename, svg_content, PlotOptions};
+
+    const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="297mm" height="210mm" viewBox="0 0 297 210"><g/></svg>"#;
+
+    #[test]
+    fn crop() {
+        let svg = crop_svg(SVG.as_bytes(), &[10.0, 20.0, 110.0, 70.0]).unwrap();
+        assert_eq!(
+            String::from_utf8(svg).unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="50mm" viewBox="10 20 100 50"><g/></svg>"#
+        );
+    }
+    #[test]
+    fn crop_needs_mm() {
+        let svg = SVG.replace("297mm", "1123").replace("210mm", "794");
+        assert!(crop_svg(svg.as_bytes(), &[10.0, 20.0, 110.0, 70.0]).is_err());
+        let svg = SVG.replace("viewBox=\"0 0 297 210\"", "viewBox=\"0 0 1123 600\"");
+        assert!(crop_svg(svg.as_bytes(), &[10.0, 20.0, 110.0, 70.0]).is_err());
+    }
+
+    #[test]
+    fn item_groups() {
+        let content = svg_content(SVG.as_bytes()).unwrap();
+        assert_eq!(content, "<g/>");
+        let svg = group_svg(SVG.as_bytes(), "wire", r#"<path d="M 0 0 L 1 0"/>"#).unwrap();
+        assert!(String::from_utf8(svg)
+            .unwrap()
+            .ends_with(r#"<g/><g class="wire"><path d="M 0 0 L 1 0"/></g></svg>"#));
+        assert!(svg_content(b"<g/>").is_err());
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(PlotOptions::for_file("plot.PNG").unwrap().format, "png");
+        assert_eq!(PlotOptions::for_file("dir.v2/plot").unwrap().format, "svg");
+        assert!(PlotOptions::for_file("plot.jpg").is_err());
+    }
+    #[test]
+    fn page_filenames() {
+        assert_eq!(page_filename("plot.svg", "filter"), "plot-filter.svg");
+        assert_eq!(page_filename("out.v2/plot.svg", "filter"), "out.v2/plot-filter.svg");
+        assert_eq!(page_filename("out.v2/plot", "filter"), "out.v2/plot-filter");
+    }
+}
@@ -0,0 +1,156 @@
+#![allow(clippy::borrow_deref_ref)]
+use std::collections::HashMap;
+
+use elektron_sexp::{SchemaElement, Symbol};
+use pyo3::prelude::*;
+
+use crate::annotate;
+use crate::orientation;
+use crate::error::Error;
+use crate::Draw;
+
+/// A symbol placed in the schema.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct PlacedSymbol {
+    #[pyo3(get)]
+    pub reference: String,
+    #[pyo3(get)]
+    pub value: String,
+    #[pyo3(get)]
+    pub lib_id: String,
+    #[pyo3(get)]
+    pub unit: u32,
+    #[pyo3(get)]
+    pub page: usize,
+    #[pyo3(get)]
+    pub pos: (f64, f64),
+    #[pyo3(get)]
+    pub angle: f64,
+    #[pyo3(get)]
+    pub mirror: Option<String>,
+    #[pyo3(get)]
+    pub properties: HashMap<String, String>,
+}
+
+#[pymethods]
+impl PlacedSymbol {
+    fn __repr__(&self) -> String {
+        format!(
+            "PlacedSymbol({}, {}, {}, unit={}, pos=({:.2}, {:.2}))",
+            self.reference, self.value, self.lib_id, self.unit, self.pos.0, self.pos.1
+        )
+    }
+}
+
+impl PlacedSymbol {
+    fn from(symbol: &Symbol, page: usize) -> Self {
+        let properties: HashMap<String, String> = symbol
+            .property
+            .iter()
+            .map(|p| (p.key.to_string(), p.value.to_string()))
+            .collect();
+        Self {
+            reference: properties.get("Reference").cloned().unwrap_or_default(),
+            value: properties.get("Value").cloned().unwrap_or_default(),
+            lib_id: symbol.lib_id.to_string(),
+            unit: symbol.unit,
+            page,
+            pos: (symbol.at[0], symbol.at[1]),
+            angle: symbol.angle,
+            mirror: symbol.mirror.clone(),
+            properties,
+        }
+    }
+}
+
+/// A pin of a placed symbol with the absolute position.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct PlacedPin {
+    #[pyo3(get)]
+    pub number: String,
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub pin_type: String,
+    #[pyo3(get)]
+    pub unit: u32,
+    #[pyo3(get)]
+    pub pos: (f64, f64),
+}
+
+#[pymethods]
+impl PlacedPin {
+    fn __repr__(&self) -> String {
+        format!(
+            "PlacedPin({}, {}, {}, pos=({:.2}, {:.2}))",
+            self.number, self.name, self.pin_type, self.pos.0, self.pos.1
+        )
+    }
+}
+
+impl Draw {
+    /// get all symbols in the schema.
+    pub(crate) fn placed_symbols(&self) -> Result<Vec<PlacedSymbol>, Error> {
+        let mut symbols = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                if let SchemaElement::Symbol(symbol) = item {
+                    symbols.push(PlacedSymbol::from(symbol, page));
+                }
+            }
+        }
+        Ok(symbols)
+    }
+
+    /// get the symbol by reference and unit.
+    pub(crate) fn placed_symbol(&self, reference: &str, unit: u32) -> Result<PlacedSymbol, Error> {
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                if let SchemaElement::Symbol(symbol) = item {
+                    if symbol.unit == unit && annotate::reference(symbol) == Some(reference) {
+                        return Ok(PlacedSymbol::from(symbol, page));
+                    }
+                }
+            }
+        }
+        Err(Error::SymbolNotFound(reference.to_string()))
+    }
+
+    /// get the pins of all units of the symbol.
+    pub(crate) fn placed_pins(&self, reference: &str) -> Result<Vec<PlacedPin>, Error> {
+        let mut pins: Vec<PlacedPin> = Vec::new();
+        for page in 0..self.schema.pages.len() {
+            for item in self.schema.iter(page)? {
+                if let SchemaElement::Symbol(symbol) = item {
+                    if annotate::reference(symbol) != Some(reference) {
+                        continue;
+                    }
+                    let lib = self
+                        .schema
+                        .get_library(symbol.lib_id.as_str())
+                        .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                    for pin in lib.pins(symbol.unit)? {
+                        if pins.iter().any(|p| p.number == pin.number.0) {
+                            continue;
+                        }
+                        let pos = orientation::symbol_transform(symbol, &pin.at)?;
+                        pins.push(PlacedPin {
+                            number: pin.number.0.to_string(),
+                            name: pin.name.0.to_string(),
+                            pin_type: pin.pin_type.to_string(),
+                            unit: symbol.unit,
+                            pos: (pos[0], pos[1]),
+                        });
+                    }
+                }
+            }
+        }
+        if pins.is_empty() {
+            Err(Error::SymbolNotFound(reference.to_string()))
+        } else {
+            Ok(pins)
+        }
+    }
+}
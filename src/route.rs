@@ -0,0 +1,344 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use elektron_sexp::{Bounds, Junction, SchemaElement, Stroke, Wire};
+use ndarray::{arr1, arr2, Array1};
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::grid::on_grid;
+use crate::orientation;
+use crate::Draw;
+
+/// number of grid cells the search area is extended around the pins.
+const ROUTE_MARGIN: i64 = 40;
+/// extra cost for every change of direction.
+const TURN_COST: u32 = 5;
+
+type Cell = (i64, i64);
+
+const DIRECTIONS: [Cell; 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+fn cell(grid: f64, pos: &Array1<f64>) -> Cell {
+    ((pos[0] / grid).round() as i64, (pos[1] / grid).round() as i64)
+}
+
+/// Obstacles for the router on the current page.
+struct Obstacles {
+    boxes: Vec<(Cell, Cell)>,
+    horizontal: HashSet<Cell>,
+    vertical: HashSet<Cell>,
+    points: HashSet<Cell>,
+}
+
+impl Obstacles {
+    /// check if the cell can be entered in the direction.
+    fn blocked(&self, cell: &Cell, direction: &Cell) -> bool {
+        if self.points.contains(cell) {
+            return true;
+        }
+        if (direction.1 == 0 && self.horizontal.contains(cell))
+            || (direction.0 == 0 && self.vertical.contains(cell))
+        {
+            return true;
+        }
+        self.boxes.iter().any(|(min, max)| {
+            cell.0 > min.0 && cell.0 < max.0 && cell.1 > min.1 && cell.1 < max.1
+        })
+    }
+}
+
+/// get the wire points from the corners of the route.
+///
+/// the corners are on the grid, off grid pins are connected with a short
+/// orthogonal stub to the first and last grid point.
+fn wire_points(grid: f64, start: &Array1<f64>, end: &Array1<f64>, corners: &[Cell]) -> Vec<Array1<f64>> {
+    let to_pos = |c: &Cell| {
+        arr1(&[
+            (c.0 as f64 * grid * 10000.0).round() / 10000.0,
+            (c.1 as f64 * grid * 10000.0).round() / 10000.0,
+        ])
+    };
+    let stub = |pin: &Array1<f64>, first: &Array1<f64>| {
+        let mut stub = vec![pin.clone()];
+        if (pin[0] - first[0]).abs() > f64::EPSILON && (pin[1] - first[1]).abs() > f64::EPSILON {
+            stub.push(arr1(&[first[0], pin[1]]));
+        }
+        stub
+    };
+
+    let grid_points: Vec<Array1<f64>> = corners.iter().map(to_pos).collect();
+    let mut points = stub(start, &grid_points[0]);
+    points.extend(grid_points.iter().cloned());
+    points.extend(stub(end, &grid_points[grid_points.len() - 1]).into_iter().rev());
+    points.dedup_by(|a, b| (a[0] - b[0]).abs() < f64::EPSILON && (a[1] - b[1]).abs() < f64::EPSILON);
+    points
+}
+
+impl Draw {
+    /// collect the obstacles on the current page.
+    fn obstacles(&self) -> Result<Obstacles, Error> {
+        let mut obstacles = Obstacles {
+            boxes: Vec::new(),
+            horizontal: HashSet::new(),
+            vertical: HashSet::new(),
+            points: HashSet::new(),
+        };
+        for item in self.schema.iter(self.page)? {
+            match item {
+                SchemaElement::Symbol(symbol) => {
+                    let lib = self
+                        .schema
+                        .get_library(symbol.lib_id.as_str())
+                        .ok_or_else(|| Error::LibraryNotFound(symbol.lib_id.to_string()))?;
+                    let bounds = orientation::symbol_bounds(symbol, &symbol.bounds(lib)?)?;
+                    let a = cell(self.grid, &arr1(&[bounds[[0, 0]], bounds[[0, 1]]]));
+                    let b = cell(self.grid, &arr1(&[bounds[[1, 0]], bounds[[1, 1]]]));
+                    obstacles
+                        .boxes
+                        .push(((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))));
+                    for pin in lib.pins(symbol.unit)? {
+                        let pos = orientation::symbol_transform(symbol, &pin.at)?;
+                        obstacles.points.insert(cell(self.grid, &pos));
+                    }
+                }
+                SchemaElement::Wire(wire) => {
+                    let start = cell(self.grid, &arr1(&[wire.pts[[0, 0]], wire.pts[[0, 1]]]));
+                    let end = cell(self.grid, &arr1(&[wire.pts[[1, 0]], wire.pts[[1, 1]]]));
+                    obstacles.points.insert(start);
+                    obstacles.points.insert(end);
+                    if start.1 == end.1 {
+                        for x in start.0.min(end.0)..=start.0.max(end.0) {
+                            obstacles.horizontal.insert((x, start.1));
+                        }
+                    } else if start.0 == end.0 {
+                        for y in start.1.min(end.1)..=start.1.max(end.1) {
+                            obstacles.vertical.insert((start.0, y));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(obstacles)
+    }
+
+    /// find an orthogonal path between the positions.
+    ///
+    /// returns the corner points of the path.
+    fn route(&self, start: &Array1<f64>, end: &Array1<f64>) -> Result<Vec<Cell>, Error> {
+        let obstacles = self.obstacles()?;
+        let (start_cell, end_cell) = (cell(self.grid, start), cell(self.grid, end));
+        let min = (
+            start_cell.0.min(end_cell.0) - ROUTE_MARGIN,
+            start_cell.1.min(end_cell.1) - ROUTE_MARGIN,
+        );
+        let max = (
+            start_cell.0.max(end_cell.0) + ROUTE_MARGIN,
+            start_cell.1.max(end_cell.1) + ROUTE_MARGIN,
+        );
+
+        // dijkstra with a penalty for turns, the state includes the direction.
+        let mut costs: HashMap<(Cell, usize), u32> = HashMap::new();
+        let mut previous: HashMap<(Cell, usize), (Cell, usize)> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        for direction in 0..DIRECTIONS.len() {
+            costs.insert((start_cell, direction), 0);
+            queue.push(Reverse((0, start_cell, direction)));
+        }
+        let mut found: Option<(Cell, usize)> = None;
+        while let Some(Reverse((cost, current, direction))) = queue.pop() {
+            if current == end_cell {
+                found = Some((current, direction));
+                break;
+            }
+            if cost > *costs.get(&(current, direction)).unwrap_or(&u32::MAX) {
+                continue;
+            }
+            for (next_direction, step) in DIRECTIONS.iter().enumerate() {
+                let next = (current.0 + step.0, current.1 + step.1);
+                if next.0 < min.0 || next.0 > max.0 || next.1 < min.1 || next.1 > max.1 {
+                    continue;
+                }
+                if next != end_cell && obstacles.blocked(&next, step) {
+                    continue;
+                }
+                let next_cost = cost + 1 + if next_direction != direction { TURN_COST } else { 0 };
+                if next_cost < *costs.get(&(next, next_direction)).unwrap_or(&u32::MAX) {
+                    costs.insert((next, next_direction), next_cost);
+                    previous.insert((next, next_direction), (current, direction));
+                    queue.push(Reverse((next_cost, next, next_direction)));
+                }
+            }
+        }
+
+        let mut state = found.ok_or_else(|| {
+            Error::NoRoute(format!("{:?}", start.to_vec()), format!("{:?}", end.to_vec()))
+        })?;
+        let mut path = vec![state.0];
+        while let Some(prev) = previous.get(&state) {
+            path.push(prev.0);
+            state = *prev;
+        }
+        path.reverse();
+
+        // only keep the corners of the path.
+        let mut corners = vec![path[0]];
+        for i in 1..path.len() - 1 {
+            let (a, b, c) = (path[i - 1], path[i], path[i + 1]);
+            if (b.0 - a.0, b.1 - a.1) != (c.0 - b.0, c.1 - b.1) {
+                corners.push(b);
+            }
+        }
+        if path.len() > 1 {
+            corners.push(path[path.len() - 1]);
+        }
+        Ok(corners)
+    }
+
+    /// connect two pins with an orthogonal wire.
+    pub(crate) fn connect_pins(
+        &mut self,
+        start: (String, String),
+        end: (String, String),
+    ) -> Result<(), Error> {
+        let start_pos = self.pin_pos(start.0.to_string(), start.1.to_string())?;
+        let end_pos = self.pin_pos(end.0.to_string(), end.1.to_string())?;
+        let corners = self.route(&start_pos, &end_pos)?;
+
+        // the pins keep their position, the points in between are on the grid.
+        let mut points = wire_points(self.grid, &start_pos, &end_pos, &corners);
+        let last = points.len() - 1;
+        for point in points.iter_mut().take(last).skip(1) {
+            *point = self.snap_pos("wire", point.clone());
+        }
+        for (pin, pos) in [(&start, &start_pos), (&end, &end_pos)] {
+            if !on_grid(self.grid, pos[0]) || !on_grid(self.grid, pos[1]) {
+                self.warnings.push(format!(
+                    "wire connected to off grid pin {}:{}: ({:.2}, {:.2})",
+                    pin.0, pin.1, pos[0], pos[1]
+                ));
+            }
+        }
+
+        // tees into existing wires need a junction.
+        let mut junctions = Vec::new();
+        for pos in &points {
+            if self.needs_junction(pos)? {
+                junctions.push(pos.clone());
+            }
+        }
+
+        for segment in points.windows(2) {
+            let pts = arr2(&[[segment[0][0], segment[0][1]], [segment[1][0], segment[1][1]]]);
+            self.schema.push(
+                self.page,
+                SchemaElement::Wire(Wire::new(pts, Stroke::new(), uuid!())),
+            )?;
+        }
+        for junction in junctions {
+            self.schema.push(
+                self.page,
+                SchemaElement::Junction(Junction::new(junction, uuid!())),
+            )?;
+        }
+        self.last_pos = end_pos;
+        Ok(())
+    }
+
+    /// check if a new wire at the position tees into existing wires.
+    fn needs_junction(&self, pos: &Array1<f64>) -> Result<bool, Error> {
+        let at = cell(self.grid, pos);
+        let mut ends = 0;
+        for item in self.schema.iter(self.page)? {
+            match item {
+                SchemaElement::Wire(wire) => {
+                    let start = cell(self.grid, &arr1(&[wire.pts[[0, 0]], wire.pts[[0, 1]]]));
+                    let end = cell(self.grid, &arr1(&[wire.pts[[1, 0]], wire.pts[[1, 1]]]));
+                    if at == start || at == end {
+                        ends += 1;
+                    } else if (start.1 == end.1
+                        && at.1 == start.1
+                        && at.0 > start.0.min(end.0)
+                        && at.0 < start.0.max(end.0))
+                        || (start.0 == end.0
+                            && at.0 == start.0
+                            && at.1 > start.1.min(end.1)
+                            && at.1 < start.1.max(end.1))
+                    {
+                        return Ok(true);
+                    }
+                }
+                SchemaElement::Junction(junction) => {
+                    if cell(self.grid, &junction.at) == at {
+                        return Ok(false);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // the pin already has a wire, the new one makes a tee.
+        Ok(ends >= 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elektron_sexp::{SchemaElement, Stroke, Wire};
+    use ndarray::{arr1, arr2, Array1};
+    use uuid::Uuid;
+
+    use super::wire_points;
+    use crate::Draw;
+
+    fn orthogonal(points: &[Array1<f64>]) -> bool {
+        points
+            .windows(2)
+            .all(|s| (s[0][0] - s[1][0]).abs() < f64::EPSILON || (s[0][1] - s[1][1]).abs() < f64::EPSILON)
+    }
+
+    #[test]
+    fn points_on_grid() {
+        let points = wire_points(1.27, &arr1(&[0.0, 0.0]), &arr1(&[2.54, 1.27]), &[(0, 0), (2, 0), (2, 1)]);
+        assert_eq!(points, vec![arr1(&[0.0, 0.0]), arr1(&[2.54, 0.0]), arr1(&[2.54, 1.27])]);
+    }
+    #[test]
+    fn stub_to_off_grid_pin() {
+        let start = arr1(&[0.5, 0.3]);
+        let end = arr1(&[2.54, 1.5]);
+        let points = wire_points(1.27, &start, &end, &[(0, 0), (2, 0), (2, 1)]);
+        assert_eq!(points[0], start);
+        assert_eq!(points[points.len() - 1], end);
+        assert!(orthogonal(&points));
+        assert!(points.contains(&arr1(&[0.0, 0.0])));
+        assert!(points.contains(&arr1(&[2.54, 1.27])));
+    }
+    #[test]
+    fn straight_route() {
+        let draw = Draw::new(vec![], 1.27).unwrap();
+        let corners = draw.route(&arr1(&[0.0, 0.0]), &arr1(&[10.16, 0.0])).unwrap();
+        assert_eq!(corners, vec![(0, 0), (8, 0)]);
+    }
+    #[test]
+    fn route_around_wire() {
+        let mut draw = Draw::new(vec![], 1.27).unwrap();
+        draw.schema
+            .push(0, SchemaElement::Wire(Wire::new(arr2(&[[2.54, 0.0], [7.62, 0.0]]), Stroke::new(), uuid!())))
+            .unwrap();
+        let corners = draw.route(&arr1(&[0.0, 0.0]), &arr1(&[10.16, 0.0])).unwrap();
+        assert_eq!(corners[0], (0, 0));
+        assert_eq!(corners[corners.len() - 1], (8, 0));
+        assert!(corners.len() > 2);
+        assert!(corners.iter().all(|c| c.1 != 0 || c.0 < 2 || c.0 > 6));
+    }
+    #[test]
+    fn junction_at_corner() {
+        let mut draw = Draw::new(vec![], 1.27).unwrap();
+        draw.schema
+            .push(0, SchemaElement::Wire(Wire::new(arr2(&[[0.0, 2.54], [10.16, 2.54]]), Stroke::new(), uuid!())))
+            .unwrap();
+        assert!(draw.needs_junction(&arr1(&[5.08, 2.54])).unwrap());
+        assert!(draw.needs_junction(&arr1(&[0.0, 2.54])).unwrap());
+        assert!(!draw.needs_junction(&arr1(&[5.08, 5.08])).unwrap());
+    }
+}
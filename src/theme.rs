@@ -0,0 +1,274 @@
+#![allow(clippy::borrow_deref_ref)]
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyDeprecationWarning;
+use pyo3::prelude::*;
+
+use crate::error::Error;
+
+/// the environment variable with the theme name, replaced by `PlotOptions.theme`.
+const THEME_ENV: &str = "ELEKTRON_THEME";
+
+/// the default theme of the plotter.
+const DEFAULT_THEME: &str = "kicad_2000";
+/// the items that are drawn as text, the color is the fill of the text.
+const TEXT_ITEMS: [&str; 4] = ["label", "global_label", "hierarchical_label", "text"];
+
+/// the items every theme file has to define with the required keys.
+const ITEMS: [(&str, &[&str]); 12] = [
+    ("schema", &["fill"]),
+    ("wire", &["stroke", "stroke-width"]),
+    ("bus", &["stroke", "stroke-width"]),
+    ("junction", &["stroke", "stroke-width"]),
+    ("no_connect", &["stroke", "stroke-width"]),
+    ("symbol", &["stroke", "stroke-width", "fill"]),
+    ("pin", &["stroke", "stroke-width"]),
+    ("label", &["stroke", "font-family", "font-size"]),
+    ("global_label", &["stroke", "font-family", "font-size"]),
+    ("hierarchical_label", &["stroke", "font-family", "font-size"]),
+    ("text", &["stroke", "font-family", "font-size"]),
+    ("property", &["stroke", "font-family", "font-size"]),
+];
+
+/// The colors, line widths and fonts of a plot.
+///
+/// the theme is either built into the plotter or loaded from a css file
+/// with one rule per item type. theme files style svg plots only, png
+/// and pdf images can only use the built in themes.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Theme {
+    #[pyo3(get)]
+    pub name: String,
+    pub path: Option<String>,
+    pub items: HashMap<String, HashMap<String, String>>,
+}
+
+#[pymethods]
+impl Theme {
+    /// get a theme built into the plotter.
+    ///
+    /// the name is passed to the plotter, unknown names fail when plotting.
+    #[new]
+    #[args(name = "\"kicad_2000\"")]
+    pub fn new(name: &str) -> Result<Self, Error> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(|c| c == '/' || c == '\\') || name.ends_with(".css") {
+            return Err(Error::Theme(name.to_string(), String::new()));
+        }
+        Ok(Self { name: name.to_string(), path: None, items: HashMap::new() })
+    }
+
+    /// load and validate the theme from a file.
+    #[staticmethod]
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        let items = parse(path, content.as_str())?;
+        for (item, keys) in ITEMS {
+            let values = items
+                .get(item)
+                .ok_or_else(|| Error::Theme(item.to_string(), String::new()))?;
+            if let Some(key) = keys.iter().find(|key| !values.contains_key(**key)) {
+                return Err(Error::Theme(item.to_string(), format!(".{}", key)));
+            }
+        }
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        Ok(Self { name, path: Some(path.to_string()), items })
+    }
+
+    /// get the value of the theme item.
+    pub fn get(&self, item: &str, key: &str) -> Result<String, Error> {
+        self.items
+            .get(item)
+            .and_then(|values| values.get(key))
+            .cloned()
+            .ok_or_else(|| Error::Theme(item.to_string(), format!(".{}", key)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Theme({})", self.name)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self { name: String::from(DEFAULT_THEME), path: None, items: HashMap::new() }
+    }
+}
+
+impl Theme {
+    /// the built in theme the plotter draws with.
+    ///
+    /// the plotter only knows the built in themes, the items of a theme
+    /// file are applied to the plot with `Theme::css`.
+    pub fn plotter_theme(&self) -> &str {
+        if self.path.is_some() {
+            DEFAULT_THEME
+        } else {
+            self.name.as_str()
+        }
+    }
+
+    /// get the style sheet of a theme file, built in themes have none.
+    ///
+    /// the items are selected by the groups of `plot_items`, the schema
+    /// fill is the background of the image. the values replace the colors
+    /// of the plotter. pins and properties are drawn with their symbol,
+    /// the property rule styles the texts of the symbols and the pin rule
+    /// is not used.
+    pub fn css(&self) -> Option<String> {
+        self.path.as_ref()?;
+        let mut css = String::new();
+        let mut items: Vec<(&String, &HashMap<String, String>)> = self.items.iter().collect();
+        items.sort();
+        for (item, values) in items {
+            let mut values: Vec<(&String, &String)> = values.iter().collect();
+            values.sort();
+            let declarations = |values: &[(&String, &String)], rename: &[(&str, &str)]| -> String {
+                values
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = rename
+                            .iter()
+                            .find(|(from, _)| *from == key.as_str())
+                            .map_or(key.as_str(), |(_, to)| *to);
+                        // the font size has no unit in the theme.
+                        let value = if key == "font-size" && value.parse::<f64>().is_ok() {
+                            format!("{}px", value)
+                        } else {
+                            value.to_string()
+                        };
+                        format!("{}: {} !important;", key, value)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            };
+            let stroke: Vec<(&String, &String)> =
+                values.iter().filter(|(key, _)| key.as_str() == "stroke").copied().collect();
+            let rules: Vec<(String, String)> = match item.as_str() {
+                "schema" => {
+                    vec![(String::from("svg"), declarations(&values, &[("fill", "background-color")]))]
+                }
+                "pin" => Vec::new(),
+                "property" => {
+                    vec![(String::from(".symbol text"), declarations(&values, &[("stroke", "fill")]))]
+                }
+                item if TEXT_ITEMS.contains(&item) => vec![
+                    (format!(".{} text", item), declarations(&values, &[("stroke", "fill")])),
+                    (format!(".{} :not(text)", item), declarations(&stroke, &[])),
+                ],
+                item => vec![(format!(".{} *", item), declarations(&values, &[]))],
+            };
+            for (selector, declarations) in rules.into_iter().filter(|(_, d)| !d.is_empty()) {
+                css.push_str(format!("{} {{ {} }}\n", selector, declarations).as_str());
+            }
+        }
+        Some(css)
+    }
+
+    /// get the theme from the deprecated `ELEKTRON_THEME` environment variable.
+    ///
+    /// the variable is a built in theme name or the path of a theme file,
+    /// a theme file that can not be loaded is an error.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        let value = match std::env::var(THEME_ENV) {
+            Ok(value) => value,
+            Err(_) => return Ok(None),
+        };
+        Python::with_gil(|py| {
+            PyErr::warn(
+                py,
+                py.get_type::<PyDeprecationWarning>(),
+                "ELEKTRON_THEME is deprecated, set PlotOptions.theme instead",
+                1,
+            )
+        })
+        .ok();
+        let path = std::path::Path::new(value.as_str());
+        if path.is_file() || path.extension().is_some() || path.components().count() > 1 {
+            Ok(Some(Self::load(value.as_str())?))
+        } else {
+            Ok(Some(Self::new(value.as_str())?))
+        }
+    }
+}
+
+/// parse the css rules of a theme file.
+fn parse(path: &str, content: &str) -> Result<HashMap<String, HashMap<String, String>>, Error> {
+    let mut items: HashMap<String, HashMap<String, String>> = HashMap::new();
+    // remove the comments.
+    let mut css = String::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("/*") {
+        css.push_str(&rest[..start]);
+        let comment = &rest[start..];
+        rest = match comment.find("*/") {
+            Some(end) => &comment[end + 2..],
+            None => "",
+        };
+    }
+    css.push_str(rest);
+
+    for rule in css.split('}') {
+        if rule.trim().is_empty() {
+            continue;
+        }
+        let (selectors, body) = rule
+            .split_once('{')
+            .ok_or_else(|| Error::ThemeSyntax(path.to_string(), rule.trim().to_string()))?;
+        for declaration in body.split(';').filter(|d| !d.trim().is_empty()) {
+            let (key, value) = declaration
+                .split_once(':')
+                .ok_or_else(|| Error::ThemeSyntax(path.to_string(), declaration.trim().to_string()))?;
+            for selector in selectors.split(',') {
+                items
+                    .entry(selector.trim().to_string())
+                    .or_default()
+                    .insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{parse, Theme};
+
+    #[test]
+    fn theme_css() {
+        let items = parse(
+            "test.css",
+            "/* test */ schema { fill: #000; } wire, bus { stroke: #fff; stroke-width: 0.2; } \
+             label { stroke: #f00; font-size: 1.27; } pin { stroke: #0f0; }",
+        )
+        .unwrap();
+        let theme = Theme { name: String::from("test"), path: Some(String::from("test.css")), items };
+        assert_eq!(theme.plotter_theme(), "kicad_2000");
+        assert_eq!(
+            theme.css().unwrap(),
+            ".bus * { stroke: #fff !important; stroke-width: 0.2 !important; }\n\
+             .label text { font-size: 1.27px !important; fill: #f00 !important; }\n\
+             .label :not(text) { stroke: #f00 !important; }\n\
+             svg { background-color: #000 !important; }\n\
+             .wire * { stroke: #fff !important; stroke-width: 0.2 !important; }\n"
+        );
+    }
+    #[test]
+    fn builtin_theme() {
+        let theme = Theme::new("kicad_2020").unwrap();
+        assert_eq!(theme.plotter_theme(), "kicad_2020");
+        assert!(theme.css().is_none());
+        assert!(Theme::new("").is_err());
+        assert!(Theme::new("themes/dark.css").is_err());
+    }
+    #[test]
+    fn missing_theme_file() {
+        assert!(Theme::load("/no/such/theme.css").is_err());
+    }
+}
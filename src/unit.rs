@@ -0,0 +1,113 @@
+use pyo3::prelude::*;
+
+use crate::error::Error;
+
+/// one mil in mm.
+const MIL: f64 = 0.0254;
+/// one inch in mm.
+const INCH: f64 = 25.4;
+
+/// A length in mm or in grid units.
+///
+/// grid units are converted with the grid of the drawing when the item is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Mm(f64),
+    Grid(f64),
+}
+
+impl Length {
+    /// get the length in mm.
+    pub fn mm(&self, grid: f64) -> f64 {
+        match self {
+            Length::Mm(mm) => *mm,
+            Length::Grid(units) => units * grid,
+        }
+    }
+}
+
+/// a position as tuple of lengths.
+pub type Pos = (Length, Length);
+
+/// get the position in mm.
+pub fn pos_mm(pos: &Pos, grid: f64) -> (f64, f64) {
+    (pos.0.mm(grid), pos.1.mm(grid))
+}
+
+/// parse a length with an optional unit.
+///
+/// the units are `mm`, `mil`, `in` and `grid`, values without a unit are mm.
+pub fn parse_length(value: &str) -> Result<Length, Error> {
+    let value = value.trim();
+    let parse = |number: &str| {
+        number
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| Error::InvalidValue(String::from("length"), value.to_string()))
+    };
+    if let Some(number) = value.strip_suffix("grid") {
+        Ok(Length::Grid(parse(number)?))
+    } else if let Some(number) = value.strip_suffix("mil") {
+        Ok(Length::Mm(parse(number)? * MIL))
+    } else if let Some(number) = value.strip_suffix("mm") {
+        Ok(Length::Mm(parse(number)?))
+    } else if let Some(number) = value.strip_suffix("in") {
+        Ok(Length::Mm(parse(number)? * INCH))
+    } else {
+        Ok(Length::Mm(parse(value)?))
+    }
+}
+
+/// get the length from a number in mm or a string with a unit.
+pub fn extract_length(value: &'_ PyAny) -> PyResult<Length> {
+    if let Ok(length) = value.extract::<f64>() {
+        return Ok(Length::Mm(length));
+    }
+    if let Ok(length) = value.extract::<&str>() {
+        return Ok(parse_length(length)?);
+    }
+    Err(Error::UnknownType(String::from("length"), value.to_string()).into())
+}
+
+/// get the position from a tuple of lengths.
+pub fn extract_pos(value: &'_ PyAny) -> Option<Pos> {
+    let (x, y): (&PyAny, &PyAny) = value.extract().ok()?;
+    Some((extract_length(x).ok()?, extract_length(y).ok()?))
+}
+
+/// get the position from a tuple of lengths.
+///
+/// raises a `TypeError` when the value is not a tuple and a `ValueError`
+/// for lengths that can not be parsed.
+pub fn position(name: &str, value: &'_ PyAny) -> PyResult<Pos> {
+    let (x, y): (&PyAny, &PyAny) = value
+        .extract()
+        .map_err(|_| PyErr::from(Error::UnknownType(name.to_string(), value.to_string())))?;
+    Ok((extract_length(x)?, extract_length(y)?))
+}
+
+/// convert a length like `200mil` or `4grid` to mm.
+#[pyfunction(grid = "crate::grid::DEFAULT_GRID")]
+pub fn length(value: &'_ PyAny, grid: f64) -> PyResult<f64> {
+    Ok(extract_length(value)?.mm(grid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_length, Length};
+
+    #[test]
+    fn lengths() {
+        assert_eq!(parse_length("2.54").unwrap(), Length::Mm(2.54));
+        assert_eq!(parse_length("2.54mm").unwrap(), Length::Mm(2.54));
+        assert!((parse_length("100mil").unwrap().mm(1.27) - 2.54).abs() < 1e-9);
+        assert_eq!(parse_length("1in").unwrap(), Length::Mm(25.4));
+        assert!(parse_length("1ft").is_err());
+    }
+    #[test]
+    fn grid_units() {
+        assert_eq!(parse_length("4grid").unwrap(), Length::Grid(4.0));
+        assert_eq!(parse_length(" 4 grid ").unwrap().mm(2.0), 8.0);
+        assert_eq!(parse_length("-2grid").unwrap().mm(1.27), -2.54);
+    }
+}
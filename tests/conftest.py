@@ -0,0 +1,13 @@
+import os
+
+import pytest
+
+SYMBOLS = os.environ.get("KICAD_SYMBOL_DIR", "/usr/share/kicad/symbols")
+
+
+@pytest.fixture
+def symbols():
+    """the kicad symbol libraries, the test is skipped when they are not installed."""
+    if not os.path.isdir(SYMBOLS):
+        pytest.skip("kicad symbol libraries not found")
+    return [SYMBOLS]
@@ -0,0 +1,73 @@
+import pytest
+
+from elektron import Dot, Draw, ElektronError, Element, GlobalLabel, Label, Line, Sheet
+
+
+def test_duplicate_anchor(symbols):
+    draw = Draw(symbols)
+    draw.add(Dot("out"))
+    draw.add(Line())
+    with pytest.raises(ValueError):
+        draw.add(Dot("out"))
+    with pytest.raises(ValueError):
+        draw.add(Label("out"))
+    # labels with the same name connect nets.
+    draw.add(Label("in"))
+    draw.add(Line())
+    draw.add(Label("in"))
+
+
+def test_goto_anchor_on_other_page(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    draw.add(Dot("split"))
+    with draw.sheet(Sheet("filter", "filter.kicad_sch")):
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+        draw.goto(draw["split"])
+        draw.add(Element("R2", "Device:R", value="1k", unit=1))
+    pages = {s.reference: s.page for s in draw.symbols()}
+    assert pages == {"R1": 0, "C1": 1, "R2": 0}
+
+
+def test_branch_restores_page(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    draw.add(Dot("split"))
+    with draw.sheet(Sheet("filter", "filter.kicad_sch")):
+        with draw.branch():
+            draw.goto(draw["split"])
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+    pages = {s.reference: s.page for s in draw.symbols()}
+    assert pages == {"R1": 0, "C1": 1}
+
+
+def test_annotate_renames_anchors(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R5", "Device:R", value="10k", unit=1).at((50.8, 25.4)))
+    draw.add(Element("R7", "Device:R", value="10k", unit=1).at((25.4, 25.4)))
+    left = draw["R7"].pos
+    draw.annotate()
+    assert "R5" not in draw and "R7" not in draw
+    assert draw["R1"].pos == left
+
+
+def test_open_loads_anchors(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(GlobalLabel("VIN"))
+    draw.add(Line())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    path = str(tmp_path / "anchors.kicad_sch")
+    draw.write(path)
+
+    opened = Draw.open(path, symbols)
+    assert "R1" in opened and "VIN" in opened
+    with pytest.raises(ValueError):
+        opened.add(Dot("VIN"))
+
+
+def test_failed_add_has_no_anchor(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90).at((30.48, 25.4)))
+    with pytest.raises(ElektronError):
+        draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90).at((25.4, 25.4)).tox("R1", "1"))
+    assert "R2" not in draw
@@ -0,0 +1,20 @@
+from elektron import Draw, Element
+
+
+def test_units_share_the_reference(symbols):
+    draw = Draw(symbols)
+    draw.add(first := Element("U?", "Amplifier_Operational:TL072", value="TL072", unit=1))
+    draw.add(second := Element("U?", "Amplifier_Operational:TL072", value="TL072", unit=2))
+    draw.add(third := Element("U?", "Amplifier_Operational:TL072", value="TL072", unit=1))
+    assert first.reference == "U1"
+    assert second.reference == "U1"
+    assert third.reference == "U2"
+
+
+def test_annotate_by_position(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R5", "Device:R", value="1k", unit=1).at((50.8, 25.4)))
+    draw.add(Element("R7", "Device:R", value="1k", unit=1).at((25.4, 25.4)))
+    draw.annotate()
+    positions = {s.reference: s.pos[0] for s in draw.symbols()}
+    assert positions["R1"] < positions["R2"]
@@ -0,0 +1,41 @@
+import json
+
+from elektron import Draw, Element
+
+
+def parts(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1, footprint="R_0603"))
+    draw.add(Element("R2", "Device:R", value="10k", unit=1, Footprint="R_0603"))
+    draw.add(Element("R10", "Device:R", value="1k", unit=1, footprint="R_0603"))
+    draw.add(Element("R3", "Device:R", value="10k", unit=1, in_bom="no"))
+    draw.add(Element("U1", "Amplifier_Operational:TL072", value="TL072", unit=1))
+    draw.add(Element("U1", "Amplifier_Operational:TL072", value="TL072", unit=2))
+    return draw
+
+
+def test_group_by_value_and_footprint(symbols):
+    rows = json.loads(parts(symbols).bom("json"))
+    assert [(row["Reference"], row["Quantity"]) for row in rows] == [
+        ("R1, R2", 2),
+        ("R10", 1),
+        ("U1", 1),
+    ]
+    assert rows[0]["footprint"] == "R_0603"
+    assert "Footprint" not in rows[0]
+
+
+def test_merge_units(symbols):
+    csv = parts(symbols).bom("csv").splitlines()
+    assert sum(1 for line in csv if line.startswith("U1,")) == 1
+
+
+def test_excluded_from_bom(symbols):
+    bom = parts(symbols).bom("csv")
+    assert "R3" not in bom
+
+
+def test_html(symbols):
+    html = parts(symbols).bom("html")
+    assert html.startswith('<table class="bom">')
+    assert "<td>R1, R2</td><td>2</td>" in html
@@ -0,0 +1,31 @@
+import math
+
+from elektron import Draw, Element, Line
+
+
+def pin(draw, reference, number):
+    return {p.number: p.pos for p in draw.pins(reference)}[number]
+
+
+def close(a, b):
+    return all(math.isclose(x, y, abs_tol=0.01) for x, y in zip(a, b))
+
+
+def test_branch_returns_to_the_position(symbols):
+    draw = Draw(symbols)
+    draw.goto((25.4, 25.4))
+    draw.add(Line().right().length(2.54))
+    with draw.branch("down"):
+        draw.add(Line().length(5.08))
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90))
+    assert close(pin(draw, "R1", "1"), (27.94, 25.4))
+    assert draw.direction == "right"
+
+
+def test_goto_pin(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).at((25.4, 25.4)))
+    draw.add(Line().right().length(5.08))
+    draw.goto("R1", "2")
+    draw.add(Element("R2", "Device:R", value="1k", unit=1))
+    assert close(pin(draw, "R2", "1"), pin(draw, "R1", "2"))
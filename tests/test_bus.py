@@ -0,0 +1,36 @@
+from elektron import (Bus, Draw, Element, HierarchicalLabel, Label, Line, Sheet,
+                      bus_members)
+
+from test_labels import nodes
+
+
+def test_label_with_index_is_a_net(symbols):
+    draw = Draw(symbols)
+    draw.add(Label("D[3]"))
+    assert bus_members("D[3]") == ["D[3]"]
+
+
+def test_bus_alias(symbols):
+    draw = Draw(symbols)
+    draw.bus_alias("I2C", ["SDA", "SCL"])
+    draw.add(Bus().right())
+    draw.add(Label("I2C"))
+    assert draw.bus_members("I2C") == ["SDA", "SCL"]
+
+
+def test_bus_members_through_sheet_pin(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Bus().right())
+    draw.add(Label("D[0..1]"))
+    draw.add(Label("D0"))
+    draw.add(Line())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1).rotate(90))
+    with draw.sheet(Sheet("bus").pin("D[0..1]", "input")):
+        draw.add(HierarchicalLabel("D[0..1]"))
+        draw.add(Label("D0"))
+        draw.add(Line())
+        draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90))
+
+    path = str(tmp_path / "bus.cir")
+    draw.circuit([]).save(path)
+    assert set(nodes(path, "R1")) & set(nodes(path, "R2"))
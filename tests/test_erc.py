@@ -0,0 +1,28 @@
+from elektron import Dot, Draw, Element, GlobalLabel, Line
+
+
+def t_tap(symbols, junction):
+    """a resistor with pin 1 in the middle of a wire."""
+    draw = Draw(symbols)
+    draw.goto((10.16, 10.16))
+    draw.add(GlobalLabel("IN"))
+    draw.add(Line().right().length("8grid"))
+    draw.add(GlobalLabel("OUT"))
+    draw.goto((15.24, 10.16))
+    if junction:
+        draw.add(Dot())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    draw.add(Line().at("R1", "2").down())
+    draw.add(GlobalLabel("GND"))
+    return draw
+
+
+def test_t_tap_with_junction(symbols):
+    assert t_tap(symbols, True).erc() == []
+
+
+def test_t_tap_without_junction(symbols):
+    violations = t_tap(symbols, False).erc()
+    assert [(v.kind, v.reference, v.pin) for v in violations] == [
+        ("missing_junction", "R1", "1")
+    ]
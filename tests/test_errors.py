@@ -0,0 +1,39 @@
+import pytest
+
+from elektron import Draw, Element, LibraryError, PinNotFoundError, length
+
+
+def test_library_without_name(symbols):
+    draw = Draw(symbols)
+    with pytest.raises(LibraryError) as err:
+        draw.add(Element("R1", "Device", value="10k", unit=1))
+    assert err.value.library == "Device"
+
+
+def test_unknown_symbol(symbols):
+    draw = Draw(symbols)
+    with pytest.raises(LibraryError):
+        draw.add(Element("R1", "Device:NoSuchSymbol", value="10k", unit=1))
+
+
+def test_unknown_anchor_pin(symbols):
+    draw = Draw(symbols)
+    with pytest.raises(PinNotFoundError) as err:
+        draw.add(Element("R1", "Device:R", value="10k", unit=1).anchor(3))
+    assert err.value.pin == "3"
+
+
+def test_invalid_length():
+    with pytest.raises(ValueError):
+        length("3furlong")
+
+
+def test_invalid_angle(symbols):
+    draw = Draw(symbols)
+    with pytest.raises(ValueError):
+        draw.add(Element("R1", "Device:R", value="10k", unit=1).rotate(45))
+
+
+def test_wrong_type():
+    with pytest.raises(TypeError):
+        length([1.0])
@@ -0,0 +1,36 @@
+import pytest
+
+from elektron import Draw, Polyline, Rectangle, Text, length
+
+
+def test_text_and_graphics_are_written(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Text("filter stage").at((25.4, 25.4)))
+    draw.add(Polyline([(10.16, 10.16), (20.32, 10.16), (20.32, 20.32)]))
+    draw.add(Rectangle((5.08, 5.08), (30.48, 30.48)))
+    path = tmp_path / "graphics.kicad_sch"
+    draw.write(str(path))
+
+    content = path.read_text()
+    assert '"filter stage"' in content
+    # the polyline and the closed rectangle.
+    assert content.count("(polyline") == 2
+
+
+def test_positions_with_units(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Text("units").at(("20grid", "1in")))
+    draw.add(Rectangle(("200mil", "5.08mm"), ("24grid", 30.48)))
+    path = tmp_path / "units.kicad_sch"
+    draw.write(str(path))
+
+    content = path.read_text()
+    assert "(at 25.4 25.4" in content
+    assert "(xy 5.08 5.08)" in content
+    assert "(xy 30.48 30.48)" in content
+
+
+def test_length():
+    assert length("4grid") == 5.08
+    assert length("4grid", 2.0) == 8.0
+    assert length("100mil") == pytest.approx(2.54)
@@ -0,0 +1,38 @@
+import math
+
+import pytest
+
+from elektron import Draw, Element, Line
+
+
+@pytest.mark.parametrize("grid", [0, -1.27, math.nan, math.inf])
+def test_invalid_grid(symbols, grid):
+    with pytest.raises(ValueError):
+        Draw(symbols, grid)
+    draw = Draw(symbols)
+    with pytest.raises(ValueError):
+        draw.grid = grid
+    assert draw.grid == 1.27
+
+
+def test_start_on_grid(symbols):
+    draw = Draw(symbols)
+    draw.add(Line().right().length(2.54))
+    assert draw.warnings == []
+
+    draw = Draw(symbols, 2.0)
+    draw.add(Line().right().length(2.0))
+    assert draw.warnings == []
+
+
+def test_off_grid_element_warns(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1).at((10.0, 10.0)))
+    assert any(w.startswith("R1 moved to grid") for w in draw.warnings)
+
+
+def test_off_grid_wire_warns(symbols):
+    draw = Draw(symbols)
+    draw.goto((10.0, 10.0))
+    draw.add(Line().right().length(2.54))
+    assert any(w.startswith("wire moved to grid") for w in draw.warnings)
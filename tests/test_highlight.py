@@ -0,0 +1,67 @@
+import re
+
+import pytest
+
+from elektron import (Draw, ElektronError, Element, GlobalLabel, HierarchicalLabel, Line,
+                      PlotOptions, Sheet)
+
+COLOR = b'stroke="#e6194b"'
+
+
+def draw_pages(symbols):
+    draw = Draw(symbols)
+    draw.add(GlobalLabel("VIN"))
+    draw.add(Line())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1).rotate(90))
+    draw.add(Line().at("R1", "2"))
+    draw.add(Element("R3", "Device:R", value="10k", unit=1).rotate(90))
+    with draw.sheet(Sheet("load")):
+        draw.add(GlobalLabel("VIN"))
+        draw.add(Line())
+        draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90))
+    return draw
+
+
+def test_highlight_net_on_all_pages(symbols):
+    draw = draw_pages(symbols)
+    pages = dict(draw.plot(options=PlotOptions(highlight=["VIN"])))
+    assert COLOR in pages["root"]
+    assert COLOR in pages["load"]
+
+
+def test_highlight_pin(symbols):
+    draw = draw_pages(symbols)
+    pages = dict(draw.plot(options=PlotOptions(highlight=[("R3", "2")])))
+    assert COLOR in pages["root"]
+    assert COLOR not in pages["load"]
+
+
+def test_unknown_net(symbols):
+    draw = draw_pages(symbols)
+    with pytest.raises(ElektronError):
+        draw.plot(options=PlotOptions(highlight=["NOPE"]))
+
+
+def test_highlight_hierarchical_label(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    with draw.sheet(Sheet("filter").pin("IN", "input")):
+        draw.add(HierarchicalLabel("IN"))
+        draw.add(Line())
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+    pages = dict(draw.plot(options=PlotOptions(highlight=["IN"])))
+    assert COLOR in pages["filter"]
+
+
+def test_label_box_justify(symbols):
+    draw = Draw(symbols)
+    draw.goto((30.48, 50.8))
+    draw.add(GlobalLabel("VIN").rotate(180))
+    draw.add(Line().right())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1).rotate(90))
+    pages = dict(draw.plot(options=PlotOptions(highlight=["VIN"])))
+    rects = re.findall(rb'<rect x="([-\d.]+)" y="[-\d.]+" width="([\d.]+)"[^>]*' + COLOR, pages["root"])
+    assert len(rects) == 1
+    x, width = (float(v) for v in rects[0])
+    assert x < 30.48
+    assert x + width == pytest.approx(30.48)
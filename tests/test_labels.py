@@ -0,0 +1,26 @@
+from elektron import Draw, Element, GlobalLabel, Line, Sheet
+
+
+def nodes(path, reference):
+    """get the nodes of the element from the spice netlist."""
+    with open(path) as netlist:
+        for line in netlist:
+            parts = line.split()
+            if parts and parts[0] == reference:
+                return parts[1:3]
+    raise AssertionError(f"{reference} not in netlist")
+
+
+def test_global_label_joins_pages(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(GlobalLabel("VIN"))
+    draw.add(Line())
+    draw.add(Element("R1", "Device:R", value="10k", unit=1).rotate(90))
+    with draw.sheet(Sheet("load")):
+        draw.add(GlobalLabel("VIN"))
+        draw.add(Line())
+        draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90))
+
+    path = str(tmp_path / "labels.cir")
+    draw.circuit([]).save(path)
+    assert set(nodes(path, "R1")) & set(nodes(path, "R2"))
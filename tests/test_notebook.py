@@ -0,0 +1,29 @@
+import subprocess
+import sys
+
+import elektron
+from elektron import Draw, Element, Sheet
+
+
+def test_notebook_display(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    with draw.sheet(Sheet("filter")):
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+
+    assert draw._repr_svg_().count("<svg") == 1
+    assert draw._repr_png_().startswith(b"\x89PNG")
+    html = draw._repr_html_()
+    assert html.count("<figure>") == 2
+    assert "<figcaption>filter</figcaption>" in html
+
+
+def test_no_matplotlib_backend():
+    assert not hasattr(elektron, "plots")
+    assert not hasattr(elektron, "PLOTS")
+
+
+def test_import_is_silent():
+    result = subprocess.run([sys.executable, "-c", "import elektron"],
+                            capture_output=True, text=True, check=True)
+    assert result.stdout == ""
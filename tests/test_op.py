@@ -0,0 +1,43 @@
+import pytest
+
+from elektron import Draw, ElektronError, Element, GlobalLabel, Line, PlotOptions
+
+
+def divider(symbols):
+    draw = Draw(symbols)
+    draw.add(GlobalLabel("VIN"))
+    draw.add(Line())
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90))
+    draw.add(Line().at("R1", "2"))
+    draw.add(GlobalLabel("OUT"))
+    return draw
+
+
+def test_voltage_tags(symbols):
+    draw = divider(symbols)
+    options = PlotOptions(operating_point={"v(vin)": 5.0, "v(out)": 2.0})
+    svg = dict(draw.plot(options=options))["root"]
+    assert "5.000 V".encode() in svg
+    assert "2.000 V".encode() in svg
+
+
+def test_resistor_current_from_voltages(symbols):
+    draw = divider(symbols)
+    options = PlotOptions(operating_point={"v(vin)": 5.0, "v(out)": 2.0})
+    svg = dict(draw.plot(options=options))["root"]
+    assert "3.000 mA".encode() in svg
+
+
+def test_saved_current(symbols):
+    draw = divider(symbols)
+    options = PlotOptions(operating_point={"v(vin)": 5.0, "v(out)": 2.0, "@r1[i]": 0.002})
+    svg = dict(draw.plot(options=options))["root"]
+    assert "2.000 mA".encode() in svg
+    assert "3.000 mA".encode() not in svg
+
+
+def test_overlay_svg_only(symbols):
+    draw = divider(symbols)
+    options = PlotOptions(format="png", operating_point={"v(vin)": 5.0})
+    with pytest.raises(ElektronError, match="only svg images"):
+        draw.plot(options=options)
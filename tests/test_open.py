@@ -0,0 +1,14 @@
+from elektron import Draw, Element, Line
+
+
+def test_open_continues_drawing(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    path = str(tmp_path / "open.kicad_sch")
+    draw.write(path)
+
+    opened = Draw.open(path, symbols)
+    opened.add(Line().at("R1", "2"))
+    opened.add(Element("R2", "Device:R", value="1k", unit=1))
+    assert [s.reference for s in opened.symbols()] == ["R1", "R2"]
+    assert opened.symbol("R2").pos != opened.symbol("R1").pos
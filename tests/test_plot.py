@@ -0,0 +1,60 @@
+import re
+
+import pytest
+
+from elektron import Draw, ElektronError, Element, PlotOptions, Sheet, SymbolNotFoundError
+
+
+def draw_pages(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    with draw.sheet(Sheet("filter", "filter.kicad_sch")):
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+    return draw
+
+
+def test_plot_selected_pages(symbols, tmp_path):
+    draw = draw_pages(symbols)
+    pages = draw.plot(options=PlotOptions(pages=[1]))
+    assert [name for name, _ in pages] == ["filter"]
+
+    draw.plot(str(tmp_path / "plot.svg"))
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["plot-filter.svg", "plot-root.svg"]
+
+
+def test_unknown_extension(symbols, tmp_path):
+    draw = draw_pages(symbols)
+    with pytest.raises(ValueError):
+        draw.plot(str(tmp_path / "plot.jpg"))
+
+
+def test_crop_to_symbols_on_other_pages(symbols):
+    draw = draw_pages(symbols)
+    with pytest.raises(SymbolNotFoundError):
+        draw.plot(options=PlotOptions(pages=[0], references=["C1"]))
+    [(name, _)] = draw.plot(options=PlotOptions(references=["C1"]))
+    assert name == "filter"
+
+
+def test_view_box_is_in_mm(symbols):
+    draw = draw_pages(symbols)
+    [(_, svg)] = draw.plot(options=PlotOptions(pages=[0]))
+    tag = re.search(rb"<svg[^>]*>", svg).group(0).decode()
+    width = re.search(r' width="([0-9.]+)mm"', tag).group(1)
+    height = re.search(r' height="([0-9.]+)mm"', tag).group(1)
+    view_box = re.search(r' viewBox="([^"]+)"', tag).group(1).split()
+    assert [float(v) for v in view_box[2:]] == pytest.approx([float(width), float(height)])
+
+
+def test_export_pdf_ignores_crop(symbols, tmp_path):
+    draw = draw_pages(symbols)
+    path = tmp_path / "schema.pdf"
+    draw.export_pdf(str(path), PlotOptions(references=["R1"], highlight=[("R1", "1")]))
+    assert path.read_bytes().startswith(b"%PDF")
+
+
+def test_crop_svg_only(symbols):
+    draw = draw_pages(symbols)
+    assert "svg" in PlotOptions.__doc__
+    with pytest.raises(ElektronError, match="only svg images"):
+        draw.plot(options=PlotOptions(format="png", area=((0, 0), (50.8, 50.8))))
@@ -0,0 +1,24 @@
+import math
+
+import pytest
+
+from elektron import Draw, Element, SymbolNotFoundError
+
+
+def test_symbol_and_pins(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    symbol = draw.symbol("R1")
+    assert (symbol.reference, symbol.value, symbol.lib_id) == ("R1", "10k", "Device:R")
+    assert symbol.properties["Value"] == "10k"
+
+    pins = {p.number: p for p in draw.pins("R1")}
+    assert sorted(pins) == ["1", "2"]
+    (x1, y1), (x2, y2) = pins["1"].pos, pins["2"].pos
+    assert math.isclose(math.hypot(x2 - x1, y2 - y1), 7.62, abs_tol=0.01)
+
+
+def test_unknown_symbol(symbols):
+    draw = Draw(symbols)
+    with pytest.raises(SymbolNotFoundError):
+        draw.symbol("R1")
@@ -0,0 +1,29 @@
+import os
+
+from elektron import Dot, Draw, Element, HierarchicalLabel, Line, Sheet
+
+
+def test_hierarchy_round_trip(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    with draw.sheet(Sheet("filter", "filter.kicad_sch").pin("IN", "input")):
+        draw.add(HierarchicalLabel("IN"))
+        draw.add(Line())
+        draw.add(Element("C1", "Device:C", value="100n", unit=1))
+    path = str(tmp_path / "main.kicad_sch")
+    draw.write(path)
+    assert os.path.exists(tmp_path / "filter.kicad_sch")
+
+    opened = Draw.open(path, symbols)
+    pages = {s.reference: s.page for s in opened.symbols()}
+    assert pages == {"R1": 0, "C1": 1}
+
+
+def test_symbol_pins_before_sheet_pins(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    with draw.sheet(Sheet("R1").pin("1", "input")):
+        pass
+    draw.add(dot := Dot().at("R1", "1"))
+    pin = [p for p in draw.pins("R1") if p.number == "1"][0]
+    assert tuple(dot.pos) == pin.pos
@@ -0,0 +1,75 @@
+import math
+import re
+
+import pytest
+
+from elektron import Draw, ElektronError, Element
+
+
+def target(draw, reference, pin):
+    return {p.number: p.pos for p in draw.pins(reference)}[pin]
+
+
+def test_toy_stretches_vertically(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).at((25.4, 76.2)))
+    end = target(draw, "R1", "1")
+    draw.add(Element("R2", "Device:R", value="1k", unit=1).at((50.8, 25.4)).toy("R1", "1"))
+
+    x, y = draw.symbol("R2").pos
+    assert math.isclose(x, 50.8, abs_tol=0.01)
+    assert math.isclose(y, (25.4 + end[1]) / 2, abs_tol=0.01)
+
+
+def test_tox_stretches_rotated(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90).at((76.2, 25.4)))
+    end = target(draw, "R1", "2")
+    draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90).at((25.4, 25.4)).tox("R1", "2"))
+
+    x, y = draw.symbol("R2").pos
+    assert math.isclose(x, (25.4 + end[0]) / 2, abs_tol=0.01)
+    assert math.isclose(y, 25.4, abs_tol=0.01)
+
+
+def wires(draw, tmp_path):
+    """get the wires from the written schema."""
+    path = tmp_path / "stretch.kicad_sch"
+    draw.write(str(path))
+    xy = r"\(xy ([-\d.]+) ([-\d.]+)\)"
+    return [
+        tuple(float(v) for v in match)
+        for match in re.findall(r"\(wire\s+\(pts\s+" + xy + r"\s+" + xy, path.read_text())
+    ]
+
+
+def assert_outside(draw, tmp_path, reference, axis):
+    """the pins are between the wires and no wire runs over the element."""
+    pins = sorted(p.pos[axis] for p in draw.pins(reference))
+    found = wires(draw, tmp_path)
+    assert len(found) == 2
+    for wire in found:
+        low, high = sorted((wire[axis], wire[axis + 2]))
+        assert high <= pins[0] + 0.01 or low >= pins[1] - 0.01
+
+
+def test_tox_rotated_270(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90).at((76.2, 25.4)))
+    draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(270).at((25.4, 25.4)).tox("R1", "1"))
+    assert_outside(draw, tmp_path, "R2", 0)
+
+
+def test_toy_mirrored(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).at((25.4, 76.2)))
+    draw.add(Element("R2", "Device:R", value="1k", unit=1).mirror("x").at((50.8, 25.4)).toy("R1", "1"))
+    assert_outside(draw, tmp_path, "R2", 1)
+
+
+def test_target_too_close(symbols):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="1k", unit=1).rotate(90).at((30.48, 25.4)))
+    with pytest.raises(ElektronError):
+        draw.add(Element("R2", "Device:R", value="1k", unit=1).rotate(90).at((25.4, 25.4)).tox("R1", "1"))
+    assert "R2" not in [s.reference for s in draw.symbols()]
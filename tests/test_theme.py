@@ -0,0 +1,49 @@
+import pytest
+
+from elektron import Draw, ElektronError, Element, Line, PlotOptions, Theme, theme
+
+
+def test_theme_file_styles_the_plot(symbols):
+    draw = Draw(symbols)
+    draw.add(Line().right().length(5.08))
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    [(_, svg)] = draw.plot(options=PlotOptions(theme=theme("dark")))
+    assert b"<style>" in svg
+    assert b"svg { background-color: #1e1e1e !important; }" in svg
+    # the wire is in its own group and the group gets the wire color.
+    assert b'<g class="wire">' in svg
+    assert b".wire * { stroke: #4ec9b0 !important;" in svg
+    assert b'<g class="symbol">' in svg
+
+    with pytest.raises(Exception, match="only be used for svg"):
+        draw.plot(options=PlotOptions(format="png", theme=theme("dark")))
+
+
+def test_builtin_theme_names():
+    assert Theme("kicad_2020").name == "kicad_2020"
+    with pytest.raises(Exception):
+        Theme("")
+
+
+def test_theme_from_environment(monkeypatch):
+    monkeypatch.setenv("ELEKTRON_THEME", "kicad_2000")
+    with pytest.warns(DeprecationWarning):
+        options = PlotOptions()
+    assert options.theme.name == "kicad_2000"
+
+
+def test_invalid_theme_from_environment(monkeypatch, tmp_path):
+    monkeypatch.setenv("ELEKTRON_THEME", str(tmp_path / "missing.css"))
+    with pytest.warns(DeprecationWarning), pytest.raises(OSError):
+        PlotOptions()
+
+
+def test_export_pdf_rejects_theme_file(symbols, tmp_path):
+    draw = Draw(symbols)
+    draw.add(Element("R1", "Device:R", value="10k", unit=1))
+    path = tmp_path / "schema.pdf"
+    with pytest.raises(ElektronError, match="built in themes"):
+        draw.export_pdf(str(path), PlotOptions(theme=theme("monochrome")))
+    assert not path.exists()
+    draw.export_pdf(str(path), PlotOptions(theme=Theme("kicad_2000")))
+    assert path.read_bytes().startswith(b"%PDF")